@@ -1,10 +1,27 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use self::erc20::{Erc20, Erc20Ref};
+pub use self::erc20::{Checkpoint, Erc20, Erc20Ref, MigrationError, Role};
+pub use token_traits::Error;
 
 #[ink::contract]
 mod erc20 {
-    use ink::storage::Mapping;
+    use ink::prelude::string::String;
+    use ink::storage::traits::ManualKey;
+    use ink::storage::{Lazy, Mapping};
+    use token_traits::{
+        Erc20Allowance, Erc20Burnable, Erc20Mintable, Erc20Permit, Error, PSP22Metadata, Result,
+    };
+
+    /// The version of the storage layout. Version 1 added the roles, checkpoints, permit
+    /// nonces and metadata, each in its own cell, so the storage root is still only the
+    /// total supply.
+    const STORAGE_VERSION: u32 = 1;
+    /// The storage key of the storage version.
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
+
+    /// Whose value checkpoints record: the balance of an owner, or the total supply for
+    /// `None`.
+    type CheckpointOwner = Option<AccountId>;
 
     /// A simple ERC-20 contract.
     #[ink(storage)]
@@ -17,6 +34,63 @@ mod erc20 {
         /// Mapping of the token amount which an account is allowed to withdraw
         /// from another account.
         allowances: Mapping<(AccountId, AccountId), Balance>,
+        /// The version of the storage layout, see `migrate`. Storage written before
+        /// versioning has no version (version 0).
+        storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
+        /// The (role, account) pairs of the granted roles.
+        roles: Mapping<(Role, AccountId), ()>,
+        /// Mapping from owner and index to the checkpoints of its balance, ordered by
+        /// block. The checkpoints of the total supply have no owner (`None`).
+        ///
+        /// Every checkpoint has its own cell, so none is ever dropped: whoever causes a
+        /// new one pays the storage deposit for it.
+        checkpoints: Mapping<(CheckpointOwner, u32), Checkpoint>,
+        /// Mapping from owner to the number of checkpoints of its balance, of the total
+        /// supply for `None`.
+        checkpoint_counts: Mapping<CheckpointOwner, u32>,
+        /// Mapping from owner to the nonce its next permit is signed with.
+        nonces: Mapping<AccountId, u64>,
+        /// The name of the token, if set.
+        name: Lazy<String>,
+        /// The symbol of the token, if set.
+        symbol: Lazy<String>,
+        /// The number of decimals a balance is shown with.
+        decimals: Lazy<u8>,
+        /// The genesis hash of the chain, which the permits are signed for.
+        genesis_hash: Lazy<Hash>,
+    }
+
+    /// The value of a balance or the total supply from the end of `block` onwards.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Checkpoint {
+        pub block: BlockNumber,
+        pub value: Balance,
+    }
+
+    /// The roles that can be granted to an account.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// Grants and revokes roles.
+        Admin,
+        /// Mints tokens to any account.
+        Minter,
+        /// Burns tokens from any account.
+        Burner,
+    }
+
+    /// The storage migration error types.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum MigrationError {
+        /// Returned if the storage is already at the current storage version.
+        AlreadyMigrated,
+        /// Returned if the caller is neither an admin nor the contract itself.
+        MissingRole,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -40,84 +114,170 @@ mod erc20 {
         value: Balance,
     }
 
-    /// The ERC-20 error types.
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub enum Error {
-        /// Returned if not enough balance to fulfill a request is available.
-        InsufficientBalance,
-        /// Returned if not enough allowance to fulfill a request is available.
-        InsufficientAllowance,
+    /// Event emitted when a role is granted to `account`.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
     }
 
-    /// The ERC-20 result type.
-    pub type Result<T> = core::result::Result<T, Error>;
+    /// Event emitted when a role is revoked from `account`.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+    }
 
     impl Erc20 {
-        /// Creates a new ERC-20 contract with the specified initial supply.
+        /// Creates a new ERC-20 contract with the specified initial supply and metadata.
+        /// The caller becomes the admin. Permits are signed for the chain with
+        /// `genesis_hash`.
         #[ink(constructor)]
-        pub fn new(total_supply: Balance) -> Self {
-            let mut balances = Mapping::default();
+        pub fn new(
+            total_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            genesis_hash: Hash,
+        ) -> Self {
             let caller = Self::env().caller();
-            balances.insert(caller, &total_supply);
+            let mut erc20 = Self::default();
+            if let Some(name) = name {
+                erc20.name.set(&name);
+            }
+            if let Some(symbol) = symbol {
+                erc20.symbol.set(&symbol);
+            }
+            erc20.decimals.set(&decimals);
+            erc20.genesis_hash.set(&genesis_hash);
+            erc20.set_balance(&caller, total_supply);
+            erc20.set_total_supply(total_supply);
             Self::env().emit_event(Transfer {
                 from: None,
                 to: Some(caller),
                 value: total_supply,
             });
-            Self {
-                total_supply,
-                balances,
-                allowances: Default::default(),
-            }
+            erc20.storage_version.set(&STORAGE_VERSION);
+            erc20.roles.insert((Role::Admin, caller), &());
+            erc20
         }
 
-        /// Returns the total token supply.
+        /// Returns the balance of `owner` at the end of `block`.
         #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
-            self.total_supply
+        pub fn balance_of_at(&self, owner: AccountId, block: BlockNumber) -> Balance {
+            self.value_at(Some(owner), block, self.balance_of_impl(&owner))
         }
 
-        /// Returns the account balance for the specified `owner`.
+        /// Returns the total token supply at the end of `block`.
+        #[ink(message)]
+        pub fn total_supply_at(&self, block: BlockNumber) -> Balance {
+            self.value_at(None, block, self.total_supply)
+        }
+
+        /// Grants `role` to `account`.
         ///
-        /// Returns `0` if the account is non-existent.
+        /// A `RoleGranted` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller is not an admin.
         #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> Balance {
-            self.balance_of_impl(&owner)
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.insert((role, account), &());
+            self.env().emit_event(RoleGranted { role, account });
+            Ok(())
         }
 
-        /// Returns the account balance for the specified `owner`.
+        /// Revokes `role` from `account`.
         ///
-        /// Returns `0` if the account is non-existent.
+        /// A `RoleRevoked` event is emitted.
         ///
-        /// # Note
+        /// # Errors
         ///
-        /// Prefer to call this method over `balance_of` since this
-        /// works using references which are more efficient in Wasm.
-        #[inline]
-        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
-            self.balances.get(owner).unwrap_or_default()
+        /// Returns `MissingRole` error if the caller is not an admin.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.remove((role, account));
+            self.env().emit_event(RoleRevoked { role, account });
+            Ok(())
         }
 
-        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        /// Returns whether `account` has `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.contains((role, account))
+        }
+
+        /// Moves the storage forward to the current storage version and returns it.
         ///
-        /// Returns `0` if no allowance has been set.
+        /// A token from before versioning (version 0) only stored the total supply,
+        /// balances and allowances, which version 1 keeps where they were. It has no
+        /// admins, so besides an admin the contract itself can migrate as part of its
+        /// code upgrade. Its new cells start out empty: no roles, checkpoints or
+        /// metadata, and the first permit of every owner is signed with nonce 0. It
+        /// didn't know its chain, so its permits are signed for `genesis_hash` from now
+        /// on.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller is neither an admin nor the
+        /// contract itself.
+        ///
+        /// Returns `AlreadyMigrated` error if the storage is at the current version.
         #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowance_impl(&owner, &spender)
+        pub fn migrate(&mut self, genesis_hash: Hash) -> core::result::Result<u32, MigrationError> {
+            let caller = self.env().caller();
+            if caller != self.env().account_id() && !self.has_role(Role::Admin, caller) {
+                return Err(MigrationError::MissingRole);
+            }
+            if self.storage_version() >= STORAGE_VERSION {
+                return Err(MigrationError::AlreadyMigrated);
+            }
+            self.genesis_hash.set(&genesis_hash);
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(STORAGE_VERSION)
+        }
+
+        /// Returns the genesis hash of the chain the permits are signed for.
+        #[ink(message)]
+        pub fn genesis_hash(&self) -> Hash {
+            self.genesis_hash.get().unwrap_or_default()
+        }
+
+        /// Returns the version of the storage layout.
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+    }
+
+    impl token_traits::Erc20 for Erc20 {
+        /// Returns the total token supply.
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balance_of_impl(&owner)
         }
 
         /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
         ///
         /// Returns `0` if no allowance has been set.
-        ///
-        /// # Note
-        ///
-        /// Prefer to call this method over `allowance` since this
-        /// works using references which are more efficient in Wasm.
-        #[inline]
-        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
-            self.allowances.get((owner, spender)).unwrap_or_default()
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance_impl(&owner, &spender)
         }
 
         /// Transfers `value` amount of tokens from the caller's account to account `to`.
@@ -129,7 +289,7 @@ mod erc20 {
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the caller's account balance.
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
             let from = self.env().caller();
             self.transfer_from_to(&from, &to, value)
         }
@@ -141,14 +301,9 @@ mod erc20 {
         ///
         /// An `Approval` event is emitted.
         #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
             let owner = self.env().caller();
-            self.allowances.insert((&owner, &spender), &value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
-            });
+            self.approve_from_to(owner, spender, value);
             Ok(())
         }
 
@@ -167,12 +322,7 @@ mod erc20 {
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the account balance of `from`.
         #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            value: Balance,
-        ) -> Result<()> {
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
             let caller = self.env().caller();
             let allowance = self.allowance_impl(&from, &caller);
             if allowance < value {
@@ -183,6 +333,303 @@ mod erc20 {
                 .insert((&from, &caller), &(allowance - value));
             Ok(())
         }
+    }
+
+    impl PSP22Metadata for Erc20 {
+        /// Returns the name of the token, if set.
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.get()
+        }
+
+        /// Returns the symbol of the token, if set.
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.get()
+        }
+
+        /// Returns the number of decimals a balance is shown with.
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals.get().unwrap_or_default()
+        }
+    }
+
+    impl Erc20Allowance for Erc20 {
+        /// Increases the allowance of `spender` over the caller's account by
+        /// `delta_value`.
+        ///
+        /// An `Approval` event with the new allowance is emitted.
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self.allowance_impl(&owner, &spender);
+            self.approve_from_to(owner, spender, allowance.saturating_add(delta_value));
+            Ok(())
+        }
+
+        /// Decreases the allowance of `spender` over the caller's account by
+        /// `delta_value`.
+        ///
+        /// An `Approval` event with the new allowance is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if the allowance is less than
+        /// `delta_value`.
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self.allowance_impl(&owner, &spender);
+            if allowance < delta_value {
+                return Err(Error::InsufficientAllowance);
+            }
+            self.approve_from_to(owner, spender, allowance - delta_value);
+            Ok(())
+        }
+    }
+
+    impl Erc20Permit for Erc20 {
+        /// Allows `spender` to withdraw up to the `value` amount from the account `owner`,
+        /// authorized by the ECDSA `signature` of `owner` over the
+        /// `token_traits::permit_hash`. The permit is valid until the timestamp `deadline`.
+        ///
+        /// It overwrites the current allowance with `value` and uses up the nonce of
+        /// `owner`, an `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `PermitExpired` error if the deadline has passed.
+        ///
+        /// Returns `InvalidSignature` error if the permit is not signed by `owner`.
+        ///
+        /// Returns `NonceOverflow` error if the nonce of `owner` is used up.
+        #[ink(message)]
+        fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+            let nonce = self.nonces(owner);
+            let hash = token_traits::permit_hash(
+                self.genesis_hash(),
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            );
+            if token_traits::recover_signer(&signature, &hash) != Some(owner) {
+                return Err(Error::InvalidSignature);
+            }
+            let next_nonce = nonce.checked_add(1).ok_or(Error::NonceOverflow)?;
+            self.nonces.insert(owner, &next_nonce);
+            self.approve_from_to(owner, spender, value);
+            Ok(())
+        }
+
+        /// Returns the nonce the next permit of `owner` is signed with.
+        #[ink(message)]
+        fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or_default()
+        }
+    }
+
+    impl Erc20Mintable for Erc20 {
+        /// Creates `value` amount of tokens on the account `to`, increasing the total
+        /// supply.
+        ///
+        /// On success a `Transfer` event from `None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller is not a minter.
+        ///
+        /// Returns `SupplyOverflow` error if the total supply would overflow.
+        #[ink(message)]
+        fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_role(Role::Minter)?;
+            let total_supply = self
+                .total_supply
+                .checked_add(value)
+                .ok_or(Error::SupplyOverflow)?;
+            // A balance is never more than the total supply.
+            let to_balance = self.balance_of_impl(&to) + value;
+            self.set_balance(&to, to_balance);
+            self.set_total_supply(total_supply);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+    }
+
+    impl Erc20Burnable for Erc20 {
+        /// Destroys `value` amount of tokens from the account `from`, reducing the total
+        /// supply.
+        ///
+        /// Every holder can burn its own tokens without a role, e.g. the DAO burns the
+        /// tokens it takes on a ragequit. Burning the tokens of another account takes the
+        /// `Burner` role.
+        ///
+        /// On success a `Transfer` event to `None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `MissingRole` error if the caller is neither `from` nor a burner.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            if self.env().caller() != from {
+                self.ensure_role(Role::Burner)?;
+            }
+            let from_balance = self.balance_of_impl(&from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance);
+            }
+            self.set_balance(&from, from_balance - value);
+            self.set_total_supply(self.total_supply - value);
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+            Ok(())
+        }
+    }
+
+    #[ink(impl)]
+    impl Erc20 {
+        /// Returns `MissingRole` error if the caller does not have `role`.
+        #[inline]
+        fn ensure_role(&self, role: Role) -> Result<()> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole);
+            }
+            Ok(())
+        }
+
+        /// Returns the account balance for the specified `owner`.
+        ///
+        /// Returns `0` if the account is non-existent.
+        ///
+        /// # Note
+        ///
+        /// Prefer to call this method over `balance_of` since this
+        /// works using references which are more efficient in Wasm.
+        #[inline]
+        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+        ///
+        /// Returns `0` if no allowance has been set.
+        ///
+        /// # Note
+        ///
+        /// Prefer to call this method over `allowance` since this
+        /// works using references which are more efficient in Wasm.
+        #[inline]
+        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        /// Sets the allowance of `spender` over the account `owner` to `value`.
+        ///
+        /// An `Approval` event is emitted.
+        fn approve_from_to(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((&owner, &spender), &value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+
+        /// Sets the balance of `owner` to `value` and records it in its checkpoints.
+        fn set_balance(&mut self, owner: &AccountId, value: Balance) {
+            let previous = self.balance_of_impl(owner);
+            self.write_checkpoint(Some(*owner), previous, value);
+            self.balances.insert(owner, &value);
+        }
+
+        /// Sets the total supply to `value` and records it in its checkpoints.
+        fn set_total_supply(&mut self, value: Balance) {
+            self.write_checkpoint(None, self.total_supply, value);
+            self.total_supply = value;
+        }
+
+        /// Records that the value of the checkpoints of `owner` changes from `previous`
+        /// to `value` in the current block.
+        ///
+        /// The first checkpoint, at block 0, holds the value from before the first
+        /// recorded change. There is at most one checkpoint per block.
+        fn write_checkpoint(&mut self, owner: CheckpointOwner, previous: Balance, value: Balance) {
+            let block = self.env().block_number();
+            let mut count = self.checkpoint_counts.get(owner).unwrap_or_default();
+            if count == 0 {
+                self.checkpoints.insert(
+                    (owner, 0),
+                    &Checkpoint {
+                        block: 0,
+                        value: previous,
+                    },
+                );
+                count = 1;
+            }
+            let last = self.checkpoint(owner, count - 1);
+            if last.block != block {
+                count += 1;
+            }
+            self.checkpoints
+                .insert((owner, count - 1), &Checkpoint { block, value });
+            self.checkpoint_counts.insert(owner, &count);
+        }
+
+        /// Returns the value of the checkpoints of `owner` at the end of `block` by
+        /// binary search, `current` if nothing has been recorded.
+        fn value_at(
+            &self,
+            owner: CheckpointOwner,
+            block: BlockNumber,
+            current: Balance,
+        ) -> Balance {
+            let count = self.checkpoint_counts.get(owner).unwrap_or_default();
+            if count == 0 {
+                return current;
+            }
+            // The first checkpoint is at block 0, so the last one at or before `block`
+            // is in `low..high`.
+            let (mut low, mut high) = (0, count);
+            while high - low > 1 {
+                let middle = low + (high - low) / 2;
+                if self.checkpoint(owner, middle).block <= block {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            self.checkpoint(owner, low).value
+        }
+
+        /// Returns the checkpoint of `owner` at `index`, which is below its count.
+        fn checkpoint(&self, owner: CheckpointOwner, index: u32) -> Checkpoint {
+            self.checkpoints
+                .get((owner, index))
+                .expect("checkpoints below the count are written")
+        }
 
         /// Transfers `value` amount of tokens from the caller's account to account `to`.
         ///
@@ -203,9 +650,9 @@ mod erc20 {
                 return Err(Error::InsufficientBalance);
             }
 
-            self.balances.insert(from, &(from_balance - value));
+            self.set_balance(from, from_balance - value);
             let to_balance = self.balance_of_impl(to);
-            self.balances.insert(to, &(to_balance + value));
+            self.set_balance(to, to_balance + value);
             self.env().emit_event(Transfer {
                 from: Some(*from),
                 to: Some(*to),
@@ -218,8 +665,10 @@ mod erc20 {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use token_traits::Erc20 as _;
 
         use ink::primitives::{Clear, Hash};
+        use ink::storage::traits::StorageKey;
 
         type Event = <Erc20 as ::ink::reflect::ContractEventBase>::Type;
 
@@ -276,7 +725,7 @@ mod erc20 {
         #[ink::test]
         fn new_works() {
             // Constructor works.
-            let _erc20 = Erc20::new(100);
+            let _erc20 = Erc20::new(100, None, None, 0, Hash::default());
 
             // Transfer event triggered during initial construction.
             let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
@@ -294,7 +743,7 @@ mod erc20 {
         #[ink::test]
         fn total_supply_works() {
             // Constructor works.
-            let erc20 = Erc20::new(100);
+            let erc20 = Erc20::new(100, None, None, 0, Hash::default());
             // Transfer event triggered during initial construction.
             let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
             assert_transfer_event(
@@ -311,7 +760,7 @@ mod erc20 {
         #[ink::test]
         fn balance_of_works() {
             // Constructor works
-            let erc20 = Erc20::new(100);
+            let erc20 = Erc20::new(100, None, None, 0, Hash::default());
             // Transfer event triggered during initial construction
             let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
             assert_transfer_event(
@@ -330,7 +779,7 @@ mod erc20 {
         #[ink::test]
         fn transfer_works() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
             // Transfer event triggered during initial construction.
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
@@ -361,7 +810,7 @@ mod erc20 {
         #[ink::test]
         fn invalid_transfer_should_fail() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             assert_eq!(erc20.balance_of(accounts.bob), 0);
@@ -395,7 +844,7 @@ mod erc20 {
         #[ink::test]
         fn transfer_from_works() {
             // Constructor works.
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
             // Transfer event triggered during initial construction.
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
@@ -443,7 +892,7 @@ mod erc20 {
 
         #[ink::test]
         fn allowance_must_not_change_on_failed_transfer() {
-            let mut erc20 = Erc20::new(100);
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             // Alice approves Bob for token transfers on her behalf.
@@ -474,6 +923,327 @@ mod erc20 {
             )
         }
 
+        #[ink::test]
+        fn burn_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Alice burns 10 of her tokens.
+            assert_eq!(erc20.burn(accounts.alice, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 90);
+            assert_eq!(erc20.total_supply(), 90);
+
+            // Check the transfer event relating to the burn.
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(AccountId::from([0x01; 32])),
+                None,
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn invalid_burn_should_fail() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Bob fails to burn tokens he doesn't own.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.burn(accounts.bob, 10),
+                Err(Error::InsufficientBalance)
+            );
+            // Bob fails to burn Alice's tokens without the burner role.
+            assert_eq!(erc20.burn(accounts.alice, 10), Err(Error::MissingRole));
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Alice is the admin, but not a minter.
+            assert_eq!(erc20.mint(accounts.bob, 10), Err(Error::MissingRole));
+            assert_eq!(erc20.grant_role(Role::Minter, accounts.alice), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+            assert_eq!(erc20.total_supply(), 110);
+
+            // Check the transfer event relating to the mint.
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(
+                &emitted_events[2],
+                None,
+                Some(AccountId::from([0x02; 32])),
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn mint_overflow_fails() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(Role::Minter, accounts.alice), Ok(()));
+            assert_eq!(
+                erc20.mint(accounts.bob, Balance::MAX - 99),
+                Err(Error::SupplyOverflow)
+            );
+            assert_eq!(erc20.mint(accounts.bob, Balance::MAX - 100), Ok(()));
+            assert_eq!(erc20.total_supply(), Balance::MAX);
+        }
+
+        /// A holder without a role burns its own tokens, but not those of others.
+        #[ink::test]
+        fn self_burn_needs_no_role() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(!erc20.has_role(Role::Burner, accounts.bob));
+            assert_eq!(erc20.burn(accounts.bob, 4), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 6);
+            assert_eq!(erc20.total_supply(), 96);
+            assert_eq!(erc20.burn(accounts.alice, 4), Err(Error::MissingRole));
+        }
+
+        #[ink::test]
+        fn roles_work() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(erc20.has_role(Role::Admin, accounts.alice));
+
+            // Alice makes Bob a burner.
+            assert_eq!(erc20.grant_role(Role::Burner, accounts.bob), Ok(()));
+            assert!(erc20.has_role(Role::Burner, accounts.bob));
+
+            // Bob burns Alice's tokens, but can't grant roles.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn(accounts.alice, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 90);
+            assert_eq!(erc20.total_supply(), 90);
+            assert_eq!(
+                erc20.grant_role(Role::Minter, accounts.bob),
+                Err(Error::MissingRole)
+            );
+            assert_eq!(
+                erc20.revoke_role(Role::Burner, accounts.bob),
+                Err(Error::MissingRole)
+            );
+
+            // Alice revokes the burner role of Bob.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.revoke_role(Role::Burner, accounts.bob), Ok(()));
+            assert!(!erc20.has_role(Role::Burner, accounts.bob));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn(accounts.alice, 10), Err(Error::MissingRole));
+        }
+
+        #[ink::test]
+        fn checkpoints_work() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(Role::Minter, accounts.alice), Ok(()));
+
+            // Block 1: Alice transfers 10 tokens to Bob, twice.
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            // Block 3: Alice mints 50 tokens to Bob and Bob burns 5.
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.mint(accounts.bob, 50), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn(accounts.bob, 5), Ok(()));
+
+            assert_eq!(erc20.balance_of_at(accounts.alice, 0), 100);
+            assert_eq!(erc20.balance_of_at(accounts.alice, 1), 80);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 0), 0);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 2), 20);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 3), 65);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 10), 65);
+            assert_eq!(erc20.total_supply_at(2), 100);
+            assert_eq!(erc20.total_supply_at(3), 145);
+            // Eve never had tokens.
+            assert_eq!(erc20.balance_of_at(accounts.eve, 2), 0);
+        }
+
+        #[ink::test]
+        fn tiny_transfers_keep_the_history() {
+            let mut erc20 = Erc20::new(1_000, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Block 1: Alice gives Bob the 100 tokens a vote snapshots.
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            // Eve sends Bob 1 token in each of the next 200 blocks, to push the snapshot
+            // out of his history.
+            assert_eq!(erc20.transfer(accounts.eve, 200), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            for _ in 0..200 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+                assert_eq!(erc20.transfer(accounts.bob, 1), Ok(()));
+            }
+            // Every block of Bob's history is still answered.
+            assert_eq!(erc20.checkpoint_counts.get(Some(accounts.bob)), Some(202));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 0), 0);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 1), 100);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 101), 200);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 201), 300);
+            assert_eq!(erc20.balance_of_at(accounts.bob, 1_000), 300);
+            // The total supply didn't change.
+            assert_eq!(erc20.total_supply_at(201), 1_000);
+        }
+
+        #[ink::test]
+        fn permit_works() {
+            use sp_core::{ecdsa, Pair};
+
+            let genesis_hash = Hash::from([7; 32]);
+            let mut erc20 = Erc20::new(100, None, None, 0, genesis_hash);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            // Alice transfers tokens to an account with an ECDSA key.
+            let pair = ecdsa::Pair::from_seed(&[1; 32]);
+            let owner = AccountId::from(sp_core::blake2_256(pair.public().as_ref()));
+            assert_eq!(erc20.transfer(owner, 50), Ok(()));
+            let deadline = 1_000;
+            let sign_for = |genesis_hash, pair: &ecdsa::Pair, value, nonce| {
+                let hash = token_traits::permit_hash(
+                    genesis_hash,
+                    contract,
+                    owner,
+                    accounts.bob,
+                    value,
+                    nonce,
+                    deadline,
+                );
+                pair.sign_prehashed(&hash).0
+            };
+            let sign =
+                |pair: &ecdsa::Pair, value, nonce| sign_for(genesis_hash, pair, value, nonce);
+
+            // Bob submits the permit of the owner.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let other = ecdsa::Pair::from_seed(&[2; 32]);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 30, deadline, sign(&other, 30, 0)),
+                Err(Error::InvalidSignature)
+            );
+            let signature = sign(&pair, 30, 0);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 40, deadline, signature),
+                Err(Error::InvalidSignature)
+            );
+            // A permit for the token on another chain is rejected.
+            let other_chain = sign_for(Hash::from([8; 32]), &pair, 30, 0);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 30, deadline, other_chain),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 30, deadline, signature),
+                Ok(())
+            );
+            assert_eq!(erc20.allowance(owner, accounts.bob), 30);
+            assert_eq!(erc20.nonces(owner), 1);
+            // The permit can't be replayed.
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 30, deadline, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(erc20.transfer_from(owner, accounts.bob, 30), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 30);
+
+            // The permit expires after the deadline.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(deadline + 1);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, deadline, sign(&pair, 10, 1)),
+                Err(Error::PermitExpired)
+            );
+        }
+
+        token_traits::erc20_allowance_tests!(|supply| Erc20::new(
+            supply,
+            None,
+            None,
+            0,
+            Hash::default()
+        ));
+
+        #[ink::test]
+        fn metadata_works() {
+            // The metadata is optional.
+            let erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            assert_eq!(erc20.token_name(), None);
+            assert_eq!(erc20.token_symbol(), None);
+            assert_eq!(erc20.token_decimals(), 0);
+
+            let erc20 = Erc20::new(
+                100,
+                Some(String::from("Governance")),
+                Some(String::from("GOV")),
+                10,
+                Hash::default(),
+            );
+            assert_eq!(erc20.token_name(), Some(String::from("Governance")));
+            assert_eq!(erc20.token_symbol(), Some(String::from("GOV")));
+            assert_eq!(erc20.token_decimals(), 10);
+        }
+
+        /// Storage from before versioning is migrated and keeps its balances.
+        #[ink::test]
+        fn migrate_works() {
+            let mut erc20 = Erc20::new(100, None, None, 0, Hash::default());
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.storage_version(), 1);
+            let genesis_hash = Hash::from([7; 32]);
+            assert_eq!(
+                erc20.migrate(genesis_hash),
+                Err(MigrationError::AlreadyMigrated)
+            );
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.revoke_role(Role::Admin, accounts.alice), Ok(()));
+
+            // Encode the storage root of a deployment from before versioning, only the
+            // total supply, without a storage version, roles or genesis hash.
+            let root_key = <Erc20 as StorageKey>::KEY;
+            ink::env::set_contract_storage(&root_key, &100u128);
+            ink::env::clear_contract_storage(&STORAGE_VERSION_KEY);
+            ink::env::clear_contract_storage(&erc20.genesis_hash.key());
+            let mut erc20: Erc20 = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(erc20.storage_version(), 0);
+            // It has no admins, so only the contract itself can migrate it.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.migrate(genesis_hash),
+                Err(MigrationError::MissingRole)
+            );
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract);
+            assert_eq!(erc20.migrate(genesis_hash), Ok(1));
+            assert_eq!(
+                erc20.migrate(genesis_hash),
+                Err(MigrationError::AlreadyMigrated)
+            );
+
+            // Read it back.
+            ink::env::set_contract_storage(&root_key, &erc20);
+            let erc20: Erc20 = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(erc20.storage_version(), 1);
+            assert_eq!(erc20.genesis_hash(), genesis_hash);
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(erc20.balance_of(accounts.alice), 90);
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+            assert!(!erc20.has_role(Role::Admin, accounts.alice));
+        }
+
         /// For calculating the event topic hash.
         struct PrefixedValue<'a, 'b, T> {
             pub prefix: &'a [u8],
@@ -525,13 +1295,14 @@ mod erc20 {
     mod e2e_tests {
         use super::*;
         use ink_e2e::build_message;
+        use token_traits::Erc20 as _;
         type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
         #[ink_e2e::test]
         async fn e2e_transfer(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
             let total_supply = 1_000_000_000;
-            let constructor = Erc20Ref::new(total_supply);
+            let constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
             let contract_acc_id = client
                 .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
                 .await
@@ -575,7 +1346,7 @@ mod erc20 {
         async fn e2e_allowances(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
             let total_supply = 1_000_000_000;
-            let constructor = Erc20Ref::new(total_supply);
+            let constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
             let contract_acc_id = client
                 .instantiate("erc20", &ink_e2e::bob(), constructor, 0, None)
                 .await
@@ -0,0 +1,334 @@
+#[cfg(test)]
+mod test {
+    use crate::dao::{Config, Dao, VoteType};
+    use ink::env::DefaultEnvironment;
+    use ink::primitives::AccountId;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::collections::{BTreeMap, BTreeSet};
+    type Balance = u128;
+    const DAYS: u64 = 60 * 1_000;
+    const VETO_THRESHOLD: u8 = 20;
+    // Accounts that can call the DAO, the first one (Alice) is the contract itself.
+    const ACCOUNTS: usize = 6;
+    // Members of the DAO, the other accounts are no members.
+    const MEMBERS: usize = 4;
+
+    // Where the voting power of a random run comes from.
+    #[derive(Debug, Clone)]
+    enum Source {
+        // One vote per member.
+        Membership,
+        // The governance token balance of each account.
+        Token(Vec<Balance>),
+    }
+
+    // A message (or a passing of time) in a random run against the DAO.
+    #[derive(Debug, Clone)]
+    enum Op {
+        Propose {
+            caller: usize,
+            to: usize,
+            amount: Balance,
+            duration: u64,
+        },
+        Vote {
+            caller: usize,
+            proposal_id: u64,
+            yes: bool,
+        },
+        Fund {
+            caller: usize,
+            amount: Balance,
+        },
+        Execute {
+            caller: usize,
+            proposal_id: u64,
+        },
+        AdvanceTime(u64),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            1 => (0..ACCOUNTS, 1..ACCOUNTS, 0..2_000u128, 0..3u64).prop_map(
+                |(caller, to, amount, duration)| Op::Propose {
+                    caller,
+                    to,
+                    amount,
+                    duration,
+                }
+            ),
+            // Votes are the most common and mostly `Yes`, to get proposals accepted.
+            3 => (0..ACCOUNTS, 1..6u64, prop::bool::weighted(0.75)).prop_map(|(caller, proposal_id, yes)| {
+                Op::Vote {
+                    caller,
+                    proposal_id,
+                    yes,
+                }
+            }),
+            1 => (0..ACCOUNTS, 0..1_000u128).prop_map(|(caller, amount)| Op::Fund { caller, amount }),
+            2 => (0..ACCOUNTS, 1..6u64).prop_map(|(caller, proposal_id)| Op::Execute {
+                caller,
+                proposal_id
+            }),
+            1 => (0..2 * DAYS).prop_map(Op::AdvanceTime),
+        ]
+    }
+
+    // What the run expects of the DAO, kept next to it.
+    #[derive(Default)]
+    struct Model {
+        // The proposals that have been executed.
+        executed: BTreeSet<u64>,
+        // The accepted votes, whether they are `Yes` and their weight, by (proposal,
+        // voter).
+        voted: BTreeMap<(u64, AccountId), (bool, Balance)>,
+        // The value the contract was created with.
+        init_balance: Balance,
+        // The value the treasury was funded with.
+        funded: Balance,
+        // The value that left the contract.
+        outflow: Balance,
+    }
+
+    proptest! {
+        // Failing cases are shrunk and printed, not written next to the sources.
+        #![proptest_config(ProptestConfig {
+            failure_persistence: None,
+            ..ProptestConfig::with_cases(128)
+        })]
+
+        #[test]
+        fn voting_engine_invariants(
+            init_balance in 0..10_000u128,
+            quorum in 1..=50u8,
+            ops in vec(op(), 1..96),
+        ) {
+            ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+                run(Source::Membership, init_balance, quorum, ops);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn token_voting_invariants(
+            balances in vec(0..1_000u128, ACCOUNTS),
+            init_balance in 0..10_000u128,
+            quorum in 1..=ACCOUNTS as u8,
+            ops in vec(op(), 1..96),
+        ) {
+            ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+                run(Source::Token(balances), init_balance, quorum, ops);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    // Run the `ops` against a fresh DAO and check the invariants after every step. The
+    // off-chain environment can't call a governance token, so for a token based DAO the
+    // run stands in for the token and votes with the account's balance as voting power.
+    fn run(source: Source, init_balance: Balance, quorum: u8, ops: Vec<Op>) {
+        let accounts = accounts();
+        set_sender(accounts[0]);
+        set_balance(contract_id(), init_balance);
+        let mut dao = match source {
+            Source::Membership => Dao::new_membership(
+                accounts[1..=MEMBERS].to_vec(),
+                quorum,
+                VETO_THRESHOLD,
+                Vec::new(),
+                Config::default(),
+            ),
+            Source::Token(_) => Dao::new(
+                [0x08; 32].into(),
+                quorum,
+                VETO_THRESHOLD,
+                Vec::new(),
+                Config::default(),
+            ),
+        };
+        let mut model = Model {
+            init_balance,
+            ..Default::default()
+        };
+
+        for op in ops {
+            match op {
+                Op::Propose {
+                    caller,
+                    to,
+                    amount,
+                    duration,
+                } => {
+                    set_sender(accounts[caller]);
+                    let _ = dao.propose(accounts[to], amount, duration);
+                }
+                Op::Vote {
+                    caller,
+                    proposal_id,
+                    yes,
+                } => {
+                    let voter = accounts[caller];
+                    set_sender(voter);
+                    let vote_type = if yes { VoteType::Yes } else { VoteType::No };
+                    let (result, weight) = match &source {
+                        Source::Membership => (dao.vote(proposal_id, vote_type), 1),
+                        // Accounts without tokens can't vote.
+                        Source::Token(balances) if balances[caller] == 0 => continue,
+                        Source::Token(balances) => (
+                            dao.voting_open(proposal_id, voter).and_then(|()| {
+                                dao.record_vote(proposal_id, voter, balances[caller], vote_type)
+                            }),
+                            balances[caller],
+                        ),
+                    };
+                    if result.is_ok() {
+                        assert!(
+                            model
+                                .voted
+                                .insert((proposal_id, voter), (yes, weight))
+                                .is_none(),
+                            "{voter:?} voted twice on proposal {proposal_id}"
+                        );
+                    }
+                }
+                Op::Fund { caller, amount } => {
+                    set_sender(accounts[caller]);
+                    // The transferred value is added to the contract balance.
+                    set_balance(contract_id(), get_balance(contract_id()) + amount);
+                    set_value_transferred(amount);
+                    assert_eq!(dao.fund(), Ok(()));
+                    set_value_transferred(0);
+                    model.funded += amount;
+                }
+                Op::Execute {
+                    caller,
+                    proposal_id,
+                } => {
+                    set_sender(accounts[caller]);
+                    let balance = get_balance(contract_id());
+                    if dao.execute(proposal_id).is_ok() {
+                        assert!(
+                            model.executed.insert(proposal_id),
+                            "proposal {proposal_id} executed twice"
+                        );
+                        let amount = dao.get_proposal(proposal_id).unwrap().amount;
+                        assert_eq!(get_balance(contract_id()), balance - amount);
+                        model.outflow += amount;
+                    } else {
+                        assert_eq!(get_balance(contract_id()), balance);
+                    }
+                }
+                Op::AdvanceTime(time) => set_block_timestamp(get_block_timestamp() + time),
+            }
+            check_invariants(&dao, &model, &accounts);
+        }
+    }
+
+    fn check_invariants(dao: &Dao, model: &Model, accounts: &[AccountId]) {
+        for proposal_id in 1..=dao.next_proposal_id {
+            // The tally equals the sum of the vote receipts, and of the weights of the
+            // accepted votes per side. Every receipt matches its accepted vote.
+            let votes = dao.get_votes(proposal_id).unwrap();
+            let weights = |side: bool| {
+                model
+                    .voted
+                    .iter()
+                    .filter(|((id, _), (yes, _))| *id == proposal_id && *yes == side)
+                    .map(|(_, (_, weight))| weight)
+                    .sum::<Balance>()
+            };
+            assert_eq!(votes.total_yes, weights(true));
+            assert_eq!(votes.total_no, weights(false));
+            let receipts = accounts
+                .iter()
+                .filter_map(|voter| Some((*voter, dao.get_vote(proposal_id, *voter)?)))
+                .collect::<Vec<_>>();
+            for (voter, receipt) in &receipts {
+                assert_eq!(
+                    model.voted.get(&(proposal_id, *voter)),
+                    Some(&(receipt.vote_type == VoteType::Yes, receipt.weight))
+                );
+            }
+            assert_eq!(
+                votes.total_yes + votes.total_no,
+                receipts
+                    .iter()
+                    .map(|(_, receipt)| receipt.weight)
+                    .sum::<u128>()
+            );
+            assert_eq!(
+                dao.get_voters(proposal_id).unwrap(),
+                u32::try_from(receipts.len()).unwrap()
+            );
+            assert_eq!(
+                receipts.len(),
+                model
+                    .voted
+                    .keys()
+                    .filter(|(id, _)| *id == proposal_id)
+                    .count()
+            );
+            // A proposal is executed once, and only by `execute`.
+            assert_eq!(
+                dao.get_proposal(proposal_id).unwrap().executed,
+                model.executed.contains(&proposal_id)
+            );
+        }
+        // The treasury never goes negative: the contract holds at least what is set
+        // aside, and only executed proposals took value out.
+        let balance = get_balance(contract_id());
+        assert!(
+            balance
+                >= dao.state.reward_pool
+                    + dao.state.reserved_rewards
+                    + dao.state.committee_allocations
+        );
+        assert_eq!(balance, model.init_balance + model.funded - model.outflow);
+        assert_eq!(dao.get_total_inflow(), model.funded);
+    }
+
+    fn accounts() -> Vec<AccountId> {
+        let accounts = ink::env::test::default_accounts::<DefaultEnvironment>();
+        let accounts = vec![
+            accounts.alice,
+            accounts.bob,
+            accounts.charlie,
+            accounts.django,
+            accounts.eve,
+            accounts.frank,
+        ];
+        debug_assert_eq!(accounts.len(), ACCOUNTS);
+        accounts
+    }
+
+    fn set_sender(account: AccountId) {
+        ink::env::test::set_caller::<DefaultEnvironment>(account)
+    }
+
+    fn set_balance(account: AccountId, amount: Balance) {
+        ink::env::test::set_account_balance::<DefaultEnvironment>(account, amount);
+    }
+
+    fn set_value_transferred(amount: Balance) {
+        ink::env::test::set_value_transferred::<DefaultEnvironment>(amount);
+    }
+
+    fn contract_id() -> AccountId {
+        ink::env::test::callee::<DefaultEnvironment>()
+    }
+
+    fn get_balance(account: AccountId) -> Balance {
+        ink::env::test::get_account_balance::<DefaultEnvironment>(account).unwrap_or_default()
+    }
+
+    fn get_block_timestamp() -> u64 {
+        ink::env::block_timestamp::<DefaultEnvironment>()
+    }
+
+    fn set_block_timestamp(timestamp: u64) {
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+    }
+}
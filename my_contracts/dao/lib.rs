@@ -1,24 +1,45 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use self::dao::{
+    ApprovalTier, Config, Dao, DaoRef, Error, Majority, SpendingLimit, TokenInterface,
+};
+
+mod invariants;
 mod tests;
 
 #[ink::contract]
 mod dao {
-    use ink::env::Result as EnvResult;
-    use ink::env::{
-        call::{build_call, ExecutionInput, Selector},
-        CallFlags, DefaultEnvironment,
-    };
-    use ink::storage::Mapping;
-    use ink::MessageResult;
+    use ink::codegen::{EmitEvent, TraitCallBuilder};
+    use ink::contract_ref;
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::vec::Vec;
+    use ink::primitives::KeyComposer;
+    use ink::reflect::ContractEventBase;
+    use ink::storage::traits::ManualKey;
+    use ink::storage::{Lazy, Mapping};
+    use token_traits::{Erc1155, Erc20, Erc20Burnable, PSP22Burnable, TokenId, PSP22};
 
     type Result<T> = core::result::Result<T, Error>;
     type ProposalId = u64;
     type Votes = u128;
+    type CommitteeId = u32;
+    type SpendId = u32;
+    type CommitteeApproval = (CommitteeId, SpendId, AccountId);
 
     // For testing purposes I made it minutes.
     const DAYS: u64 = 60 * 1_000;
 
+    // The version of the DAO's storage layout. The root holds the config, quorum and
+    // proposal counter and is decoded before any message runs, so a later field goes in
+    // its own `Mapping` or `Lazy` cell with a bump of this version.
+    const STORAGE_VERSION: u32 = 1;
+    // The storage key of the storage version.
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
+    // The storage keys of the proposals and the numbers of voters, whose layouts
+    // changed with versioning.
+    const UNVERSIONED_PROPOSALS_KEY: u32 = KeyComposer::from_str("Dao::proposals");
+    const UNVERSIONED_TOTAL_VOTERS_KEY: u32 = KeyComposer::from_str("Dao::total_voters");
+
     // A proposal that can be made with `fn propose`.
     #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
     #[cfg_attr(
@@ -31,6 +52,100 @@ mod dao {
         pub start: Timestamp,
         pub end: Timestamp,
         pub executed: bool,
+        pub kind: ProposalKind,
+        pub action: ProposalAction,
+        pub vetoed: bool,
+    }
+
+    // A proposal as stored before versioning, see `fn migrate`.
+    #[derive(scale::Decode, scale::Encode)]
+    struct UnversionedProposal {
+        to: AccountId,
+        amount: Balance,
+        start: Timestamp,
+        end: Timestamp,
+        executed: bool,
+    }
+
+    // The state of a `Proposal`, see `fn get_proposal_state`.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ProposalState {
+        // Voting has not started yet because of the voting delay.
+        Pending,
+        // Voting is open.
+        Active,
+        // Voting has ended and the proposal can be executed.
+        Succeeded,
+        // Voting has ended and the proposal can not be executed.
+        Defeated,
+        // The guardians vetoed the proposal.
+        Vetoed,
+        // The proposal has been executed.
+        Executed,
+        // The proposal has been amended, its successor is voted on instead.
+        Superseded,
+    }
+
+    // How the outcome of a `Proposal` is decided.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum ProposalKind {
+        // Passes when the quorum is met and the majority voted `Yes`.
+        Standard,
+        // Passes automatically unless the `No` votes reach the veto threshold.
+        Optimistic,
+    }
+
+    // What happens when a `Proposal` is executed.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum ProposalAction {
+        // Transfer `amount` of the treasury to `to`.
+        Transfer,
+        // Add `to` to the guardians.
+        AddGuardian,
+        // Remove `to` from the guardians.
+        RemoveGuardian,
+        // Add `to` to the members.
+        AddMember,
+        // Remove `to` from the members.
+        RemoveMember,
+        // Create the committee in `committee_specs` with an allocation of `amount`.
+        CreateCommittee,
+        // Return up to `amount` of the allocation of the committee in
+        // `committee_proposals` to the treasury.
+        ClawbackCommittee,
+        // Dissolve the committee in `committee_proposals` and return its allocation to
+        // the treasury.
+        DissolveCommittee,
+        // Let `to` claim `amount` every interval of the schedule in `recurring_schedules`.
+        RecurringPayment,
+        // Revoke the recurring payment in `revoke_proposals`.
+        RevokeRecurringPayment,
+        // Replace the code of the DAO with the code in `code_hashes`, see `fn migrate`.
+        SetCodeHash,
+    }
+
+    // Where the voting power of an account comes from.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum VotingSource {
+        // The balance of the governance token.
+        GovernanceToken(AccountId),
+        // One vote per member.
+        Membership,
+        // The balance of a token id (e.g. a badge) of an ERC-1155 contract.
+        Erc1155Token(AccountId, TokenId),
     }
 
     // The amount of votes on a given `Proposal`.
@@ -45,7 +160,7 @@ mod dao {
     }
 
     // Type of a vote.
-    #[derive(scale::Decode, scale::Encode, Clone, Copy)]
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq, Eq)]
     #[cfg_attr(
         feature = "std",
         derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
@@ -55,6 +170,273 @@ mod dao {
         No,
     }
 
+    // The receipt of a vote: the voting power it was cast with and its side.
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct VoteReceipt {
+        pub weight: Votes,
+        pub vote_type: VoteType,
+    }
+
+    // A cap on the value the treasury can spend per epoch.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct SpendingLimit {
+        pub amount: Balance,
+        // The duration of an epoch in days. With 0 the limit applies to the whole
+        // lifetime of the DAO.
+        pub epoch_duration: u64,
+    }
+
+    // The share of the votes that has to be `Yes` for a proposal to pass.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum Majority {
+        // More `Yes` than `No` votes.
+        Simple,
+        // At least two thirds of the votes are `Yes`.
+        TwoThirds,
+    }
+
+    // The requirements for a proposal to pass, based on the proposed amount.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct ApprovalTier {
+        // The tier applies to proposals with an amount of at least `min_amount`.
+        pub min_amount: Balance,
+        pub majority: Majority,
+        // Same as the quorum of the DAO, but for the proposals in this tier.
+        pub quorum: u8,
+    }
+
+    // Optional settings of the DAO, specified upon creation.
+    #[derive(scale::Decode, scale::Encode, Debug, Default, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Config {
+        // The maximum value the treasury can spend per epoch, no limit when `None`.
+        pub spending_limit: Option<SpendingLimit>,
+        // The reward shared by the voters of a proposal, paid from the reward pool.
+        // No rewards when 0.
+        pub voting_reward: Balance,
+        // A proposal has to meet the tier with the highest `min_amount` that is not
+        // above its amount. Without a matching tier a simple majority and the quorum
+        // of the DAO are required.
+        pub approval_tiers: Vec<ApprovalTier>,
+        // The number of days between proposing and the start of voting.
+        pub voting_delay: u64,
+        // The number of days between the end of voting and the earliest execution.
+        // Guardians can still veto and members ragequit in the meantime.
+        pub timelock: u64,
+        // Reject funding the treasury without value.
+        pub reject_zero_funding: bool,
+        // The interface (message selectors) of the governance token.
+        pub token_interface: TokenInterface,
+        // Escrow the governance tokens, or the ERC-1155 tokens, of a voter until the
+        // proposal ends, instead of only reading its balance. Has no effect on a
+        // membership based DAO.
+        pub escrow_voting: bool,
+    }
+
+    // The trait definitions in `token_traits` the governance token implements.
+    #[derive(scale::Decode, scale::Encode, Debug, Default, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum TokenInterface {
+        // `Erc20` and `Erc20Burnable`.
+        #[default]
+        Erc20,
+        // `PSP22` and `PSP22Burnable`, with the PSP22 namespaced selectors.
+        Psp22,
+    }
+
+    // A committee that can be created through a proposal.
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct CommitteeSpec {
+        pub members: Vec<AccountId>,
+        // The cap on what the committee can spend per epoch.
+        pub budget: SpendingLimit,
+        // The percentage of the members that has to approve a spend.
+        pub quorum: u8,
+        // The number of days a spend can be approved and executed.
+        pub voting_duration: u64,
+    }
+
+    // A committee spends from its own allocation of the treasury. Its spends can be
+    // executed as soon as enough members approved them.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Committee {
+        // The part of the treasury the committee can spend.
+        pub allocation: Balance,
+        pub budget: SpendingLimit,
+        pub quorum: u8,
+        pub voting_duration: u64,
+        pub total_members: u32,
+        // The spend id for the next spend.
+        pub next_spend_id: SpendId,
+    }
+
+    // A spend of a committee's allocation.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct CommitteeSpend {
+        pub to: AccountId,
+        pub amount: Balance,
+        pub end: Timestamp,
+        pub approvals: u32,
+        pub executed: bool,
+    }
+
+    // The schedule of a recurring payment.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct RecurringSchedule {
+        // The number of days between payments.
+        pub interval: u64,
+        // The number of payments.
+        pub periods: u32,
+    }
+
+    // A recurring payment, created by executing its proposal. The recipient can claim
+    // `amount` every interval until no payments are left.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct RecurringPayment {
+        pub to: AccountId,
+        pub amount: Balance,
+        pub interval: u64,
+        pub payments_left: u32,
+        pub next_claim: Timestamp,
+    }
+
+    // The outcome of the checks of `fn execute`, see `fn simulate_execute`.
+    #[derive(scale::Decode, scale::Encode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ExecutionReport {
+        pub executed: bool,
+        pub vetoed: bool,
+        pub superseded: bool,
+        pub voting_ended: bool,
+        // The approval tier that applies to the proposal.
+        pub approval_tier: ApprovalTier,
+        // Always true for an optimistic proposal.
+        pub quorum_met: bool,
+        // For an optimistic proposal: the veto threshold has not been reached.
+        pub majority_reached: bool,
+        pub treasury_sufficient: bool,
+        pub within_spending_limit: bool,
+        // The guardian, member, committee or recurring payment the proposal acts on is
+        // (still) valid.
+        pub target_valid: bool,
+        // What `fn execute` would return if voting had ended.
+        pub result: Result<()>,
+    }
+
+    // The settings and counters of the DAO. It is the part of the storage root after
+    // the proposal counter, so it also decodes the root written before versioning,
+    // see `fn migrate`.
+    #[derive(scale::Encode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct DaoState {
+        // What accounts need to vote.
+        pub voting_source: VotingSource,
+        // The number of members.
+        pub total_members: u32,
+        // The minimul number of voters required for a proposal to be executed. For a
+        // membership based DAO it is the percentage of the members instead.
+        pub quorum: u8,
+        // The percentage of the total voting power that has to vote `No` to stop an
+        // optimistic proposal.
+        pub veto_threshold: u8,
+        // The accounts that can veto a succeeded proposal.
+        pub guardians: Vec<AccountId>,
+        // The optional settings of the DAO.
+        pub config: Config,
+        // The part of the contract balance that is available for voting rewards.
+        pub reward_pool: Balance,
+        // The part of the contract balance that is reserved for unclaimed voting rewards.
+        pub reserved_rewards: Balance,
+        // The total value the treasury has been funded with.
+        pub total_inflow: Balance,
+        // The committee id for the next committee.
+        pub next_committee_id: CommitteeId,
+        // The part of the contract balance allocated to committees.
+        pub committee_allocations: Balance,
+    }
+
+    impl scale::Decode for DaoState {
+        fn decode<I: scale::Input>(input: &mut I) -> core::result::Result<Self, scale::Error> {
+            // Before versioning the root ended with the governance token and the quorum
+            // (33 bytes). A current state is always longer.
+            if input.remaining_len()? == Some(33) {
+                return Ok(DaoState {
+                    voting_source: VotingSource::GovernanceToken(AccountId::decode(input)?),
+                    total_members: 0,
+                    quorum: u8::decode(input)?,
+                    // Optimistic proposals came later, half of the voting power
+                    // vetoes one.
+                    veto_threshold: 50,
+                    guardians: Vec::new(),
+                    config: Config::default(),
+                    reward_pool: 0,
+                    reserved_rewards: 0,
+                    total_inflow: 0,
+                    next_committee_id: 0,
+                    committee_allocations: 0,
+                });
+            }
+            Ok(DaoState {
+                voting_source: VotingSource::decode(input)?,
+                total_members: u32::decode(input)?,
+                quorum: u8::decode(input)?,
+                veto_threshold: u8::decode(input)?,
+                guardians: Vec::decode(input)?,
+                config: Config::decode(input)?,
+                reward_pool: Balance::decode(input)?,
+                reserved_rewards: Balance::decode(input)?,
+                total_inflow: Balance::decode(input)?,
+                next_committee_id: CommitteeId::decode(input)?,
+                committee_allocations: Balance::decode(input)?,
+            })
+        }
+    }
+
     // Contract storage.
     #[ink(storage)]
     pub struct Dao {
@@ -65,13 +447,167 @@ mod dao {
         // Store a voter's account id, when it voted, to the proposal id.
         pub votes: Mapping<(ProposalId, AccountId), ()>,
         // Store the number of voters to a proposal id.
-        pub total_voters: Mapping<ProposalId, u8>,
+        pub total_voters: Mapping<ProposalId, u32>,
         // The proposal id for the next proposal.
         pub next_proposal_id: ProposalId,
-        // The token accounts need to vote.
-        pub governance_token: AccountId,
-        // The minimul number of voters required for a proposal to be executed.
-        pub quorum: u8,
+        // The settings and counters of the DAO, see `DaoState`.
+        pub state: DaoState,
+        // Store the members when voting is membership based.
+        pub members: Mapping<AccountId, ()>,
+        // Store a guardian's account id, when it vetoed, to the proposal id.
+        pub guardian_vetoes: Mapping<(ProposalId, AccountId), ()>,
+        // Store the value spent by the treasury to the epoch.
+        pub epoch_spent: Mapping<u64, Balance>,
+        // Store the voting reward, reserved upon the first vote, to the proposal id.
+        pub proposal_rewards: Mapping<ProposalId, Balance>,
+        // Store a voter's account id, when it claimed its reward, to the proposal id.
+        pub claimed_rewards: Mapping<(ProposalId, AccountId), ()>,
+        // Store the part of the voting reward that has not been claimed yet to the
+        // proposal id.
+        pub unclaimed_rewards: Mapping<ProposalId, Balance>,
+        // Store the number of voters that claimed their reward to the proposal id.
+        pub reward_claimants: Mapping<ProposalId, u32>,
+        // Store the total value an account funded the treasury with.
+        pub contributions: Mapping<AccountId, Balance>,
+        // Set while a message that interacts with other accounts is running. It is
+        // `Lazy` so it is written to storage right away and not at the end of the message.
+        pub reentrancy_lock: Lazy<bool>,
+        // Store the committee to create to the proposal id.
+        pub committee_specs: Mapping<ProposalId, CommitteeSpec>,
+        // Store the committee to claw back from or dissolve to the proposal id.
+        pub committee_proposals: Mapping<ProposalId, CommitteeId>,
+        // Store the committee to the committee id.
+        pub committees: Mapping<CommitteeId, Committee>,
+        // Store a member's account id to the committee id.
+        pub committee_members: Mapping<(CommitteeId, AccountId), ()>,
+        // Store the spend to the committee and spend id.
+        pub committee_spends: Mapping<(CommitteeId, SpendId), CommitteeSpend>,
+        // Store a member's account id, when it approved, to the committee and spend id.
+        pub committee_approvals: Mapping<CommitteeApproval, ()>,
+        // Store the value spent by a committee to the committee id and epoch.
+        pub committee_epoch_spent: Mapping<(CommitteeId, u64), Balance>,
+        // Store the schedule of a recurring payment to the proposal id.
+        pub recurring_schedules: Mapping<ProposalId, RecurringSchedule>,
+        // Store the recurring payment to the id of the proposal that created it.
+        pub recurring_payments: Mapping<ProposalId, RecurringPayment>,
+        // Store the recurring payment to revoke to the proposal id.
+        pub revoke_proposals: Mapping<ProposalId, ProposalId>,
+        // Store the governance tokens a voter escrowed, until withdrawn, to the proposal id.
+        pub escrows: Mapping<(ProposalId, AccountId), Balance>,
+        // Store the account that made the proposal to the proposal id.
+        pub proposers: Mapping<ProposalId, AccountId>,
+        // Store the amendment that supersedes a proposal to the proposal id.
+        pub amendments: Mapping<ProposalId, ProposalId>,
+        // Store the proposal an amendment supersedes to the amendment's id.
+        pub amended_from: Mapping<ProposalId, ProposalId>,
+        // Store the proposal whose votes an amendment carries over to the amendment's id.
+        pub carried_votes: Mapping<ProposalId, ProposalId>,
+        // Store the receipt of a vote to the proposal id and the voter's account id. A
+        // vote cast before versioning has none.
+        pub vote_receipts: Mapping<(ProposalId, AccountId), VoteReceipt>,
+        // Store the code hash to set to the proposal id.
+        pub code_hashes: Mapping<ProposalId, Hash>,
+        // The version of the storage layout, see `fn migrate`. It has its own key so it
+        // can be read whatever the layout of the storage root. Storage written before
+        // versioning has no version (version 0).
+        pub storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
+    }
+
+    #[ink(event)]
+    pub struct CommitteeCreated {
+        #[ink(topic)]
+        pub committee_id: CommitteeId,
+        pub allocation: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CommitteeDissolved {
+        #[ink(topic)]
+        pub committee_id: CommitteeId,
+        pub returned: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RecurringPaymentClaimed {
+        #[ink(topic)]
+        pub payment_id: ProposalId,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount: Balance,
+        pub payments_left: u32,
+    }
+
+    #[ink(event)]
+    pub struct VotingRewardClaimed {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub reward: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteTokensWithdrawn {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Ragequit {
+        #[ink(topic)]
+        pub member: AccountId,
+        pub amount: Balance,
+        pub share: Balance,
+    }
+
+    #[ink(event)]
+    pub struct MemberAdded {
+        #[ink(topic)]
+        pub member: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct MemberRemoved {
+        #[ink(topic)]
+        pub member: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GuardianAdded {
+        #[ink(topic)]
+        pub guardian: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GuardianRemoved {
+        #[ink(topic)]
+        pub guardian: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct VetoCast {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub guardian: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalVetoed {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        pub total_vetoes: u8,
+    }
+
+    #[ink(event)]
+    pub struct ProposalAmended {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub amendment: ProposalId,
     }
 
     // #[ink(event)]
@@ -113,12 +649,12 @@ mod dao {
     //     total_voters: u8,
     // }
 
-    // #[ink(event)]
-    // pub struct Fund {
-    //     #[ink(topic)]
-    //     from: AccountId,
-    //     amount: Balance,
-    // }
+    #[ink(event)]
+    pub struct Fund {
+        #[ink(topic)]
+        pub from: AccountId,
+        pub amount: Balance,
+    }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -149,51 +685,519 @@ mod dao {
         InsufficientTreasuryBalance,
         // Transfer for the execution of the transfer failed.
         TransferFailed,
+        // Optimistic proposal has been stopped by enough `No` votes.
+        VetoThresholdReached,
+        // Caller is not a guardian.
+        NotGuardian,
+        // Account is already a guardian.
+        AlreadyGuardian,
+        // Account is not a guardian.
+        GuardianNotFound,
+        // Guardian has already vetoed this proposal.
+        AlreadyVetoed,
+        // Proposal has been vetoed by the majority of the guardians.
+        ProposalVetoed,
+        // The amount of governance tokens to ragequit with must be higher than 0.
+        InvalidRagequitAmount,
+        // Transfer of the governance tokens to the DAO failed.
+        TokenTransferFailed,
+        // Burning the governance tokens failed.
+        TokenBurnFailed,
+        // The DAO's voting is not based on a governance token.
+        NoGovernanceToken,
+        // The DAO's voting is not membership based.
+        NoMembership,
+        // Caller is not a member.
+        NotMember,
+        // Account is already a member.
+        AlreadyMember,
+        // Account is not a member.
+        MemberNotFound,
+        // Executing the proposal would exceed the spending limit of the current epoch.
+        SpendingLimitExceeded,
+        // Caller has not voted on this proposal.
+        NotVoted,
+        // There is no voting reward for this proposal.
+        NoVotingReward,
+        // Voter has already claimed its reward for this proposal.
+        RewardAlreadyClaimed,
+        // Proposal has not been accepted by two thirds of the votes.
+        SupermajorityNotReached,
+        // Voting on the proposal has not started yet.
+        VotingNotStarted,
+        // Funding the treasury without value is rejected.
+        ZeroFunding,
+        // The DAO has been called again while it was calling another account.
+        ReentrantCall,
+        // A committee needs at least one member and a quorum of at most 100.
+        InvalidCommittee,
+        // Committee ID does not exist.
+        CommitteeNotFound,
+        // Caller is not a member of the committee.
+        NotCommitteeMember,
+        // The committee's allocation is too low for the spend.
+        InsufficientCommitteeAllocation,
+        // Spend ID does not exist.
+        SpendNotFound,
+        // Member has already approved this spend.
+        AlreadyApproved,
+        // Less members than required approved the spend.
+        CommitteeQuorumNotMet,
+        // A recurring payment needs an interval and at least one period.
+        InvalidRecurringPayment,
+        // Recurring payment does not exist.
+        RecurringPaymentNotFound,
+        // Caller is not the recipient of the recurring payment.
+        NotRecipient,
+        // The next payment can't be claimed yet.
+        PaymentNotDue,
+        // Voter has no governance tokens escrowed for the proposal.
+        NoEscrowedTokens,
+        // Caller is not the proposer of the proposal.
+        NotProposer,
+        // Only transfer proposals can be amended.
+        NotAmendable,
+        // Proposal has been superseded by an amendment.
+        ProposalSuperseded,
+        // Storage is already at the current storage version.
+        AlreadyMigrated,
+        // The timelock after voting has not passed yet.
+        ProposalTimelocked,
+        // The amount of an optimistic proposal falls in an approval tier.
+        OptimisticAmountTooHigh,
+        // No contract code has been uploaded with the code hash.
+        CodeNotFound,
     }
 
     impl Dao {
-        // Instantiate a new DAO.
+        // Instantiate a new DAO where voting power comes from the governance token.
+        #[ink(constructor, payable)]
+        pub fn new(
+            governance_token: AccountId,
+            quorum: u8,
+            veto_threshold: u8,
+            guardians: Vec<AccountId>,
+            config: Config,
+        ) -> Self {
+            Self::init(
+                VotingSource::GovernanceToken(governance_token),
+                quorum,
+                veto_threshold,
+                guardians,
+                config,
+            )
+        }
+
+        // Instantiate a new DAO where voting power comes from the balance of `token_id`
+        // in the ERC-1155 contract `erc1155`.
         #[ink(constructor, payable)]
-        pub fn new(governance_token: AccountId, quorum: u8) -> Self {
+        pub fn new_erc1155(
+            erc1155: AccountId,
+            token_id: TokenId,
+            quorum: u8,
+            veto_threshold: u8,
+            guardians: Vec<AccountId>,
+            config: Config,
+        ) -> Self {
+            Self::init(
+                VotingSource::Erc1155Token(erc1155, token_id),
+                quorum,
+                veto_threshold,
+                guardians,
+                config,
+            )
+        }
+
+        // Instantiate a new DAO where every member has one vote.
+        #[ink(constructor, payable)]
+        pub fn new_membership(
+            members: Vec<AccountId>,
+            quorum: u8,
+            veto_threshold: u8,
+            guardians: Vec<AccountId>,
+            config: Config,
+        ) -> Self {
+            let mut dao = Self::init(
+                VotingSource::Membership,
+                quorum,
+                veto_threshold,
+                guardians,
+                config,
+            );
+            for member in members {
+                if !dao.is_member(member) {
+                    dao.members.insert(member, &());
+                    dao.state.total_members += 1;
+                }
+            }
+            dao
+        }
+
+        fn init(
+            voting_source: VotingSource,
+            quorum: u8,
+            veto_threshold: u8,
+            guardians: Vec<AccountId>,
+            config: Config,
+        ) -> Self {
+            let mut unique_guardians = Vec::new();
+            for guardian in guardians {
+                if !unique_guardians.contains(&guardian) {
+                    unique_guardians.push(guardian);
+                }
+            }
             // Self::env().emit_event(DaoCreated {
             //     governance_token,
             //     quorum,
             // });
-            Dao {
+            let mut dao = Dao {
                 proposals: Mapping::default(),
                 proposal_votes: Mapping::default(),
                 votes: Mapping::default(),
                 total_voters: Mapping::default(),
                 next_proposal_id: 0,
-                governance_token,
-                quorum,
+                state: DaoState {
+                    voting_source,
+                    total_members: 0,
+                    quorum,
+                    veto_threshold,
+                    guardians: unique_guardians,
+                    config,
+                    reward_pool: 0,
+                    reserved_rewards: 0,
+                    total_inflow: 0,
+                    next_committee_id: 0,
+                    committee_allocations: 0,
+                },
+                members: Mapping::default(),
+                guardian_vetoes: Mapping::default(),
+                epoch_spent: Mapping::default(),
+                proposal_rewards: Mapping::default(),
+                claimed_rewards: Mapping::default(),
+                unclaimed_rewards: Mapping::default(),
+                reward_claimants: Mapping::default(),
+                contributions: Mapping::default(),
+                reentrancy_lock: Lazy::new(),
+                committee_specs: Mapping::default(),
+                committee_proposals: Mapping::default(),
+                committees: Mapping::default(),
+                committee_members: Mapping::default(),
+                committee_spends: Mapping::default(),
+                committee_approvals: Mapping::default(),
+                committee_epoch_spent: Mapping::default(),
+                recurring_schedules: Mapping::default(),
+                recurring_payments: Mapping::default(),
+                revoke_proposals: Mapping::default(),
+                escrows: Mapping::default(),
+                proposers: Mapping::default(),
+                amendments: Mapping::default(),
+                amended_from: Mapping::default(),
+                carried_votes: Mapping::default(),
+                vote_receipts: Mapping::default(),
+                code_hashes: Mapping::default(),
+                storage_version: Lazy::new(),
+            };
+            dao.storage_version.set(&STORAGE_VERSION);
+            // Value transferred upon creation is the first contribution to the treasury.
+            let (from, amount) = (Self::env().caller(), Self::env().transferred_value());
+            if amount > 0 {
+                dao.contribute(from, amount)
+                    .expect("no contributions have been made yet");
             }
+            dao
         }
 
         // Propose a new proposal.
         #[ink(message)]
         pub fn propose(&mut self, to: AccountId, amount: Balance, duration: u64) -> Result<()> {
-            if amount == 0 || amount > self.env().balance() {
+            self.proposal_amount_valid(amount)?;
+            self.create_proposal(
+                to,
+                amount,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::Transfer,
+            )
+        }
+
+        // Propose a new optimistic proposal, which passes at the end of its duration
+        // unless vetoed.
+        #[ink(message)]
+        pub fn propose_optimistic(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<()> {
+            self.proposal_amount_valid(amount)?;
+            self.create_proposal(
+                to,
+                amount,
+                duration,
+                ProposalKind::Optimistic,
+                ProposalAction::Transfer,
+            )
+        }
+
+        // Propose to add a guardian.
+        #[ink(message)]
+        pub fn propose_add_guardian(&mut self, guardian: AccountId, duration: u64) -> Result<()> {
+            if self.is_guardian(guardian) {
+                return Err(Error::AlreadyGuardian);
+            }
+            self.create_proposal(
+                guardian,
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::AddGuardian,
+            )
+        }
+
+        // Propose to remove a guardian.
+        #[ink(message)]
+        pub fn propose_remove_guardian(
+            &mut self,
+            guardian: AccountId,
+            duration: u64,
+        ) -> Result<()> {
+            if !self.is_guardian(guardian) {
+                return Err(Error::GuardianNotFound);
+            }
+            self.create_proposal(
+                guardian,
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::RemoveGuardian,
+            )
+        }
+
+        // Propose to add a member to a membership based DAO.
+        #[ink(message)]
+        pub fn propose_add_member(&mut self, member: AccountId, duration: u64) -> Result<()> {
+            self.membership()?;
+            if self.is_member(member) {
+                return Err(Error::AlreadyMember);
+            }
+            self.create_proposal(
+                member,
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::AddMember,
+            )
+        }
+
+        // Propose to remove a member from a membership based DAO.
+        #[ink(message)]
+        pub fn propose_remove_member(&mut self, member: AccountId, duration: u64) -> Result<()> {
+            self.membership()?;
+            if !self.is_member(member) {
+                return Err(Error::MemberNotFound);
+            }
+            self.create_proposal(
+                member,
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::RemoveMember,
+            )
+        }
+
+        // Propose to create a committee with an allocation of the treasury.
+        #[ink(message)]
+        pub fn propose_create_committee(
+            &mut self,
+            spec: CommitteeSpec,
+            allocation: Balance,
+            duration: u64,
+        ) -> Result<()> {
+            if spec.members.is_empty() || spec.quorum > 100 {
+                return Err(Error::InvalidCommittee);
+            }
+            if spec.voting_duration == 0 {
+                return Err(Error::InvalidProposalDuration);
+            }
+            self.proposal_amount_valid(allocation)?;
+            self.create_proposal(
+                self.env().account_id(),
+                allocation,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::CreateCommittee,
+            )?;
+            self.committee_specs.insert(self.next_proposal_id, &spec);
+            Ok(())
+        }
+
+        // Propose to return up to `amount` of a committee's allocation to the treasury.
+        #[ink(message)]
+        pub fn propose_clawback_committee(
+            &mut self,
+            committee_id: CommitteeId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<()> {
+            self.get_committee(committee_id)?;
+            if amount == 0 {
+                return Err(Error::InvalidProposalAmount);
+            }
+            self.create_proposal(
+                self.env().account_id(),
+                amount,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::ClawbackCommittee,
+            )?;
+            self.committee_proposals
+                .insert(self.next_proposal_id, &committee_id);
+            Ok(())
+        }
+
+        // Propose to pay `to` an `amount` every `interval` days, `periods` times.
+        #[ink(message)]
+        pub fn propose_recurring_payment(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            schedule: RecurringSchedule,
+            duration: u64,
+        ) -> Result<()> {
+            if schedule.interval == 0 || schedule.periods == 0 {
+                return Err(Error::InvalidRecurringPayment);
+            }
+            self.proposal_amount_valid(amount)?;
+            self.create_proposal(
+                to,
+                amount,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::RecurringPayment,
+            )?;
+            self.recurring_schedules
+                .insert(self.next_proposal_id, &schedule);
+            Ok(())
+        }
+
+        // Propose to revoke a recurring payment, the payments left can't be claimed
+        // anymore.
+        #[ink(message)]
+        pub fn propose_revoke_recurring_payment(
+            &mut self,
+            payment_id: ProposalId,
+            duration: u64,
+        ) -> Result<()> {
+            let payment = self.get_recurring_payment(payment_id)?;
+            self.create_proposal(
+                payment.to,
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::RevokeRecurringPayment,
+            )?;
+            self.revoke_proposals
+                .insert(self.next_proposal_id, &payment_id);
+            Ok(())
+        }
+
+        // Propose to replace the code of the DAO with the code uploaded with `code_hash`.
+        // Once executed, `fn migrate` of the new code moves the storage forward.
+        #[ink(message)]
+        pub fn propose_set_code_hash(&mut self, code_hash: Hash, duration: u64) -> Result<()> {
+            self.create_proposal(
+                self.env().account_id(),
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::SetCodeHash,
+            )?;
+            self.code_hashes.insert(self.next_proposal_id, &code_hash);
+            Ok(())
+        }
+
+        // Propose to dissolve a committee.
+        #[ink(message)]
+        pub fn propose_dissolve_committee(
+            &mut self,
+            committee_id: CommitteeId,
+            duration: u64,
+        ) -> Result<()> {
+            self.get_committee(committee_id)?;
+            self.create_proposal(
+                self.env().account_id(),
+                0,
+                duration,
+                ProposalKind::Standard,
+                ProposalAction::DissolveCommittee,
+            )?;
+            self.committee_proposals
+                .insert(self.next_proposal_id, &committee_id);
+            Ok(())
+        }
+
+        #[inline]
+        fn proposal_amount_valid(&self, amount: Balance) -> Result<()> {
+            if amount == 0 || amount > self.treasury_balance() {
                 return Err(Error::InvalidProposalAmount);
             }
+            Ok(())
+        }
+
+        #[inline]
+        fn create_proposal(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            kind: ProposalKind,
+            action: ProposalAction,
+        ) -> Result<()> {
             if duration == 0 {
                 return Err(Error::InvalidProposalDuration);
             }
+            // Optimistic proposals pass without votes, so they can't be used for the
+            // amounts that an approval tier puts a higher quorum or majority on.
+            if kind == ProposalKind::Optimistic
+                && self
+                    .state
+                    .config
+                    .approval_tiers
+                    .iter()
+                    .any(|tier| tier.min_amount <= amount)
+            {
+                return Err(Error::OptimisticAmountTooHigh);
+            }
+            // The voting delay and duration are in days.
+            let start = self
+                .state
+                .config
+                .voting_delay
+                .checked_mul(DAYS)
+                .and_then(|delay| self.env().block_timestamp().checked_add(delay))
+                .ok_or(Error::ArithmeticOverflow)?;
+            let end = duration
+                .checked_mul(DAYS)
+                .and_then(|duration| start.checked_add(duration))
+                .ok_or(Error::ArithmeticOverflow)?;
             let proposal_id = self.create_proposal_id()?;
             self.next_proposal_id += 1;
 
             // Create `Proposal`
-            let now = self.env().block_timestamp();
             self.proposals.insert(
                 proposal_id,
                 &Proposal {
                     to,
                     amount,
-                    start: now,
-                    end: now + (duration * DAYS),
+                    start,
+                    end,
                     executed: false,
+                    kind,
+                    action,
+                    vetoed: false,
                 },
             );
+            self.proposers.insert(proposal_id, &self.env().caller());
             self.proposal_votes.insert(
                 proposal_id,
                 &ProposalVotes {
@@ -218,23 +1222,144 @@ mod dao {
                 .ok_or(Error::ArithmeticOverflow)
         }
 
-        // Vote on a proposal.
+        // Amend a proposal of the caller, that is still open, with a new `to` and
+        // `amount`. The amendment is a new proposal that supersedes the original. When
+        // it keeps `to` and doesn't raise the amount, every vote for the original
+        // supports the amendment as well, so the votes, the voting reward and the
+        // escrowed tokens carry over to it. Otherwise voting starts over.
         #[ink(message)]
-        pub fn vote(&mut self, proposal_id: ProposalId, vote_type: VoteType) -> Result<()> {
+        pub fn amend(
+            &mut self,
+            proposal_id: ProposalId,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<()> {
             let proposal = match self.proposals.get(proposal_id) {
                 Some(proposal) => proposal,
                 _ => return Err(Error::ProposalNotFound),
             };
+            if self.proposers.get(proposal_id) != Some(self.env().caller()) {
+                return Err(Error::NotProposer);
+            }
+            if proposal.action != ProposalAction::Transfer {
+                return Err(Error::NotAmendable);
+            }
             self.proposal_executed(&proposal)?;
+            self.proposal_vetoed(&proposal)?;
+            self.proposal_superseded(proposal_id)?;
             if self.proposal_expired(&proposal) {
                 return Err(Error::ProposalExpired);
             }
-            let caller = self.env().caller();
-            self.has_voted(proposal_id, caller)?;
+            self.proposal_amount_valid(amount)?;
+            self.create_proposal(
+                to,
+                amount,
+                duration,
+                proposal.kind,
+                ProposalAction::Transfer,
+            )?;
+            let amendment = self.next_proposal_id;
+            self.amendments.insert(proposal_id, &amendment);
+            self.amended_from.insert(amendment, &proposal_id);
+            if to == proposal.to && amount <= proposal.amount {
+                self.carry_votes(proposal_id, amendment);
+            } else {
+                // A superseded proposal pays no voting rewards.
+                self.release_voting_reward(proposal_id)?;
+            }
+            self.emit_event(ProposalAmended {
+                proposal_id,
+                amendment,
+            });
+            Ok(())
+        }
+
+        // Move the votes and the voting reward of a superseded proposal to its
+        // amendment. The vote receipts stay with the original, see `fn voted_on`.
+        #[inline]
+        fn carry_votes(&mut self, proposal_id: ProposalId, amendment: ProposalId) {
+            let proposal_votes = self.proposal_votes.get(proposal_id).unwrap_or_default();
+            let total_voters = self.total_voters.get(proposal_id).unwrap_or_default();
+            self.proposal_votes.insert(amendment, &proposal_votes);
+            self.total_voters.insert(amendment, &total_voters);
+            if let Some(reward) = self.proposal_rewards.take(proposal_id) {
+                self.proposal_rewards.insert(amendment, &reward);
+            }
+            if let Some(unclaimed) = self.unclaimed_rewards.take(proposal_id) {
+                self.unclaimed_rewards.insert(amendment, &unclaimed);
+            }
+            self.carried_votes.insert(amendment, &proposal_id);
+        }
+
+        // Get the proposal the vote of `voter` that counts for a proposal was cast on,
+        // which is either the proposal or one of the proposals it amends.
+        #[inline]
+        fn voted_on(&self, proposal_id: ProposalId, voter: AccountId) -> Option<ProposalId> {
+            let mut proposal_id = proposal_id;
+            while !self.votes.contains((proposal_id, voter)) {
+                proposal_id = self.carried_votes.get(proposal_id)?;
+            }
+            Some(proposal_id)
+        }
+
+        // Get the receipt of the vote of `voter` that counts for a proposal, see
+        // `fn voted_on`.
+        #[inline]
+        fn vote_receipt(&self, proposal_id: ProposalId, voter: AccountId) -> Option<VoteReceipt> {
+            let proposal_id = self.voted_on(proposal_id, voter)?;
+            self.vote_receipts.get((proposal_id, voter))
+        }
+
+        // Get the last proposal of the amendment chain that carries the votes cast on a
+        // proposal.
+        #[inline]
+        fn last_carrier(&self, proposal_id: ProposalId) -> ProposalId {
+            let mut proposal_id = proposal_id;
+            while let Some(amendment) = self.amendments.get(proposal_id) {
+                if self.carried_votes.get(amendment) != Some(proposal_id) {
+                    break;
+                }
+                proposal_id = amendment;
+            }
+            proposal_id
+        }
+
+        // Get the amendment chain a proposal is part of, from the first proposal to the
+        // latest amendment.
+        #[ink(message)]
+        pub fn get_amendment_chain(&self, proposal_id: ProposalId) -> Result<Vec<ProposalId>> {
+            if !self.proposals.contains(proposal_id) {
+                return Err(Error::ProposalNotFound);
+            }
+            let mut first = proposal_id;
+            while let Some(original) = self.amended_from.get(first) {
+                first = original;
+            }
+            let mut chain = Vec::from([first]);
+            let mut last = first;
+            while let Some(amendment) = self.amendments.get(last) {
+                chain.push(amendment);
+                last = amendment;
+            }
+            Ok(chain)
+        }
 
+        // Vote on a proposal.
+        #[ink(message)]
+        pub fn vote(&mut self, proposal_id: ProposalId, vote_type: VoteType) -> Result<()> {
+            let caller = self.env().caller();
+            self.voting_open(proposal_id, caller)?;
             let voting_power = self.get_voting_power(caller)?;
-            self.add_votes(voting_power, proposal_id, vote_type)?;
-            self.votes.insert((&proposal_id, &caller), &());
+            self.record_vote(proposal_id, caller, voting_power, vote_type)?;
+            // With escrow voting the weight of the vote is held by the DAO until the
+            // proposal ends. Pulled last, after the vote is recorded.
+            if self.state.config.escrow_voting
+                && self.state.voting_source != VotingSource::Membership
+            {
+                self.escrows.insert((&proposal_id, &caller), &voting_power);
+                self.escrow_vote_tokens(caller, voting_power)?;
+            }
             // Self::env().emit_event(Vote {
             //     proposal_id,
             //     who: caller,
@@ -246,11 +1371,105 @@ mod dao {
 
         #[inline]
         fn get_voting_power(&self, caller: AccountId) -> Result<Votes> {
-            let balance = self.balance_of(caller);
-            if balance == 0 {
-                return Err(Error::InsufficientBalance);
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(governance_token) => {
+                    let balance = self.balance_of(governance_token, caller);
+                    if balance == 0 {
+                        return Err(Error::InsufficientBalance);
+                    }
+                    Ok(balance)
+                }
+                VotingSource::Membership => {
+                    if !self.is_member(caller) {
+                        return Err(Error::NotMember);
+                    }
+                    Ok(1)
+                }
+                VotingSource::Erc1155Token(erc1155, token_id) => {
+                    let token: contract_ref!(Erc1155) = erc1155.into();
+                    let balance = token.balance_of(caller, token_id);
+                    if balance == 0 {
+                        return Err(Error::InsufficientBalance);
+                    }
+                    Ok(balance)
+                }
+            }
+        }
+
+        #[inline]
+        fn total_voting_power(&self) -> Votes {
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(governance_token) => {
+                    self.total_supply(governance_token)
+                }
+                VotingSource::Membership => Votes::from(self.state.total_members),
+                VotingSource::Erc1155Token(erc1155, token_id) => {
+                    let token: contract_ref!(Erc1155) = erc1155.into();
+                    token.total_supply(token_id)
+                }
+            }
+        }
+
+        #[inline]
+        fn governance_token(&self) -> Result<AccountId> {
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(governance_token) => Ok(governance_token),
+                VotingSource::Membership | VotingSource::Erc1155Token(..) => {
+                    Err(Error::NoGovernanceToken)
+                }
+            }
+        }
+
+        #[inline]
+        fn membership(&self) -> Result<()> {
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(_) | VotingSource::Erc1155Token(..) => {
+                    Err(Error::NoMembership)
+                }
+                VotingSource::Membership => Ok(()),
+            }
+        }
+
+        // Whether `voter` can vote on a proposal, apart from its voting power.
+        #[inline]
+        pub(crate) fn voting_open(&self, proposal_id: ProposalId, voter: AccountId) -> Result<()> {
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                _ => return Err(Error::ProposalNotFound),
+            };
+            self.proposal_executed(&proposal)?;
+            self.proposal_superseded(proposal_id)?;
+            if self.proposal_pending(&proposal) {
+                return Err(Error::VotingNotStarted);
+            }
+            if self.proposal_expired(&proposal) {
+                return Err(Error::ProposalExpired);
             }
-            Ok(balance)
+            self.has_voted(proposal_id, voter)
+        }
+
+        // Count the vote of `voter` with its voting power.
+        #[inline]
+        pub(crate) fn record_vote(
+            &mut self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+            voting_power: Votes,
+            vote_type: VoteType,
+        ) -> Result<()> {
+            if self.total_voters.get(proposal_id).unwrap_or_default() == 0 {
+                self.reserve_voting_reward(proposal_id)?;
+            }
+            self.add_votes(voting_power, proposal_id, vote_type)?;
+            self.votes.insert((&proposal_id, &voter), &());
+            self.vote_receipts.insert(
+                (&proposal_id, &voter),
+                &VoteReceipt {
+                    weight: voting_power,
+                    vote_type,
+                },
+            );
+            Ok(())
         }
 
         #[inline]
@@ -281,27 +1500,41 @@ mod dao {
                 },
             };
             self.proposal_votes.insert(proposal_id, &proposal_votes);
-            let total_voters = self.total_voters.get(proposal_id).unwrap_or_default();
-            self.total_voters.insert(proposal_id, &(total_voters + 1));
+            let total_voters = self
+                .total_voters
+                .get(proposal_id)
+                .unwrap_or_default()
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.total_voters.insert(proposal_id, &total_voters);
             Ok(())
         }
 
         #[inline]
-        fn balance_of(&self, caller: AccountId) -> Balance {
-            let result = build_call::<DefaultEnvironment>()
-                .call(self.governance_token)
-                .gas_limit(0)
-                .transferred_value(0)
-                .call_flags(CallFlags::default())
-                .exec_input(
-                    ExecutionInput::new(Selector::new(ink::selector_bytes!("balance_of")))
-                        .push_arg(caller),
-                )
-                .returns::<Balance>()
-                .try_invoke();
-            match result {
-                EnvResult::Ok(MessageResult::Ok(result)) => result,
-                _ => unimplemented!(),
+        fn balance_of(&self, governance_token: AccountId, caller: AccountId) -> Balance {
+            match self.state.config.token_interface {
+                TokenInterface::Erc20 => {
+                    let token: contract_ref!(Erc20) = governance_token.into();
+                    token.balance_of(caller)
+                }
+                TokenInterface::Psp22 => {
+                    let token: contract_ref!(PSP22) = governance_token.into();
+                    token.balance_of(caller)
+                }
+            }
+        }
+
+        #[inline]
+        fn total_supply(&self, governance_token: AccountId) -> Balance {
+            match self.state.config.token_interface {
+                TokenInterface::Erc20 => {
+                    let token: contract_ref!(Erc20) = governance_token.into();
+                    token.total_supply()
+                }
+                TokenInterface::Psp22 => {
+                    let token: contract_ref!(PSP22) = governance_token.into();
+                    token.total_supply()
+                }
             }
         }
 
@@ -313,6 +1546,11 @@ mod dao {
             Ok(())
         }
 
+        #[inline]
+        fn proposal_pending(&self, proposal: &Proposal) -> bool {
+            self.env().block_timestamp() < proposal.start
+        }
+
         #[inline]
         fn proposal_expired(&self, proposal: &Proposal) -> bool {
             if self.env().block_timestamp() >= proposal.end {
@@ -321,9 +1559,14 @@ mod dao {
             false
         }
 
+        #[inline]
+        fn proposal_timelocked(&self, proposal: &Proposal) -> bool {
+            self.env().block_timestamp() < proposal.end + (self.state.config.timelock * DAYS)
+        }
+
         #[inline]
         fn has_voted(&self, proposal_id: ProposalId, voter: AccountId) -> Result<()> {
-            if self.votes.get((proposal_id, voter)).is_some() {
+            if self.voted_on(proposal_id, voter).is_some() {
                 return Err(Error::AlreadyVoted);
             }
             Ok(())
@@ -332,27 +1575,59 @@ mod dao {
         // Execute a proposal.
         #[ink(message)]
         pub fn execute(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.lock()?;
+            let result = self.execute_proposal(proposal_id);
+            self.unlock();
+            result
+        }
+
+        // Executes in checks-effects-interactions order: the proposal is marked as
+        // executed before the treasury transfers anything.
+        #[inline]
+        fn execute_proposal(&mut self, proposal_id: ProposalId) -> Result<()> {
+            // Checks
             let mut proposal = match self.proposals.get(proposal_id) {
                 Some(proposal) => proposal,
                 _ => return Err(Error::ProposalNotFound),
             };
-            self.proposal_executed(&proposal)?;
             if !self.proposal_expired(&proposal) {
                 return Err(Error::ProposalStillActive);
             }
-            let _total_voters = self.quorum_met(proposal_id)?;
-            let proposal_votes = match self.proposal_votes.get(proposal_id) {
-                Some(proposal_votes) => proposal_votes,
-                _ => panic!("Developer is a dickhead"),
-            };
-            self.proposal_pass(proposal_votes)?;
-            let treasury = self.env().balance();
-            if proposal.amount > treasury {
-                return Err(Error::InsufficientTreasuryBalance);
+            if self.proposal_timelocked(&proposal) {
+                return Err(Error::ProposalTimelocked);
+            }
+            self.execution_checks(proposal_id, &proposal)?;
+            // Effects
+            if self.spends_treasury(&proposal) {
+                self.spend(proposal.amount)?;
+            }
+            match proposal.action {
+                ProposalAction::Transfer => {}
+                ProposalAction::AddGuardian => self.add_guardian(proposal.to)?,
+                ProposalAction::RemoveGuardian => self.remove_guardian(proposal.to)?,
+                ProposalAction::AddMember => self.add_member(proposal.to)?,
+                ProposalAction::RemoveMember => self.remove_member(proposal.to)?,
+                ProposalAction::CreateCommittee => {
+                    self.create_committee(proposal_id, proposal.amount)?
+                }
+                ProposalAction::ClawbackCommittee => {
+                    self.clawback_committee(proposal_id, proposal.amount)?
+                }
+                ProposalAction::DissolveCommittee => self.dissolve_committee(proposal_id)?,
+                ProposalAction::RecurringPayment => {
+                    self.create_recurring_payment(proposal_id, &proposal)
+                }
+                ProposalAction::RevokeRecurringPayment => {
+                    self.revoke_recurring_payment(proposal_id)?
+                }
+                ProposalAction::SetCodeHash => self.set_code_hash(proposal_id)?,
             }
-            self.transfer_proposal_amount(proposal)?;
             proposal.executed = true;
             self.proposals.insert(proposal_id, &proposal);
+            // Interactions
+            if proposal.action == ProposalAction::Transfer {
+                self.transfer_proposal_amount(proposal)?;
+            }
             // Self::env().emit_event(ProposalExecuted {
             //     proposal_id,
             //     to: proposal.to,
@@ -364,23 +1639,277 @@ mod dao {
             Ok(())
         }
 
+        // Guard a message against being called again before it has finished.
+        #[inline]
+        fn lock(&mut self) -> Result<()> {
+            if self.reentrancy_lock.get().unwrap_or_default() {
+                return Err(Error::ReentrantCall);
+            }
+            self.reentrancy_lock.set(&true);
+            Ok(())
+        }
+
+        #[inline]
+        fn unlock(&mut self) {
+            self.reentrancy_lock.set(&false);
+        }
+
+        // All checks of `fn execute`, apart from whether voting has ended.
+        #[inline]
+        fn execution_checks(&self, proposal_id: ProposalId, proposal: &Proposal) -> Result<()> {
+            self.proposal_executed(proposal)?;
+            self.proposal_vetoed(proposal)?;
+            self.proposal_superseded(proposal_id)?;
+            self.proposal_accepted(proposal_id, proposal)?;
+            if self.spends_treasury(proposal) {
+                self.treasury_sufficient(proposal.amount)?;
+                self.epoch_spent_after(proposal.amount)?;
+            }
+            self.target_valid(proposal_id, proposal)
+        }
+
+        #[inline]
+        fn spends_treasury(&self, proposal: &Proposal) -> bool {
+            matches!(
+                proposal.action,
+                ProposalAction::Transfer | ProposalAction::CreateCommittee
+            )
+        }
+
+        #[inline]
+        fn treasury_sufficient(&self, amount: Balance) -> Result<()> {
+            if amount > self.treasury_balance() {
+                return Err(Error::InsufficientTreasuryBalance);
+            }
+            Ok(())
+        }
+
+        // Whether the account, committee or recurring payment the proposal acts on is
+        // still valid.
+        #[inline]
+        fn target_valid(&self, proposal_id: ProposalId, proposal: &Proposal) -> Result<()> {
+            match proposal.action {
+                ProposalAction::AddGuardian if self.is_guardian(proposal.to) => {
+                    Err(Error::AlreadyGuardian)
+                }
+                ProposalAction::RemoveGuardian if !self.is_guardian(proposal.to) => {
+                    Err(Error::GuardianNotFound)
+                }
+                ProposalAction::AddMember if self.is_member(proposal.to) => {
+                    Err(Error::AlreadyMember)
+                }
+                ProposalAction::RemoveMember if !self.is_member(proposal.to) => {
+                    Err(Error::MemberNotFound)
+                }
+                ProposalAction::ClawbackCommittee | ProposalAction::DissolveCommittee => self
+                    .get_committee(self.proposal_committee(proposal_id))
+                    .map(|_| ()),
+                ProposalAction::RevokeRecurringPayment => self
+                    .get_recurring_payment(self.revoked_payment(proposal_id))
+                    .map(|_| ()),
+                _ => Ok(()),
+            }
+        }
+
+        // Run the checks of `fn execute` as if voting on the proposal had ended.
+        #[ink(message)]
+        pub fn simulate_execute(&self, proposal_id: ProposalId) -> Result<ExecutionReport> {
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                _ => return Err(Error::ProposalNotFound),
+            };
+            let approval_tier = self.approval_tier(proposal_id, &proposal);
+            let quorum_met = proposal.kind == ProposalKind::Optimistic
+                || self.quorum_met(proposal_id, approval_tier.quorum).is_ok();
+            let proposal_votes = self.proposal_votes.get(proposal_id).unwrap_or_default();
+            let spends_treasury = self.spends_treasury(&proposal);
+            Ok(ExecutionReport {
+                executed: proposal.executed,
+                vetoed: proposal.vetoed,
+                superseded: self.amendments.contains(proposal_id),
+                voting_ended: self.proposal_expired(&proposal),
+                approval_tier,
+                quorum_met,
+                majority_reached: self
+                    .proposal_pass(&proposal, approval_tier.majority, proposal_votes)
+                    .is_ok(),
+                treasury_sufficient: !spends_treasury
+                    || self.treasury_sufficient(proposal.amount).is_ok(),
+                within_spending_limit: !spends_treasury
+                    || self.epoch_spent_after(proposal.amount).is_ok(),
+                target_valid: self.target_valid(proposal_id, &proposal).is_ok(),
+                result: self.execution_checks(proposal_id, &proposal),
+            })
+        }
+
+        #[inline]
+        fn proposal_vetoed(&self, proposal: &Proposal) -> Result<()> {
+            if proposal.vetoed {
+                return Err(Error::ProposalVetoed);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn proposal_superseded(&self, proposal_id: ProposalId) -> Result<()> {
+            if self.amendments.contains(proposal_id) {
+                return Err(Error::ProposalSuperseded);
+            }
+            Ok(())
+        }
+
+        // A proposal has succeeded when its voting period is over and it has been
+        // accepted according to its kind.
+        #[inline]
+        fn proposal_succeeded(&self, proposal_id: ProposalId, proposal: &Proposal) -> Result<()> {
+            if !self.proposal_expired(proposal) {
+                return Err(Error::ProposalStillActive);
+            }
+            self.proposal_accepted(proposal_id, proposal)
+        }
+
+        // Whether the votes meet the quorum and majority of the proposal.
+        #[inline]
+        fn proposal_accepted(&self, proposal_id: ProposalId, proposal: &Proposal) -> Result<()> {
+            let approval_tier = self.approval_tier(proposal_id, proposal);
+            if proposal.kind == ProposalKind::Standard {
+                self.quorum_met(proposal_id, approval_tier.quorum)?;
+            }
+            let proposal_votes = match self.proposal_votes.get(proposal_id) {
+                Some(proposal_votes) => proposal_votes,
+                _ => panic!("Developer is a dickhead"),
+            };
+            self.proposal_pass(proposal, approval_tier.majority, proposal_votes)
+        }
+
+        // The approval tier that applies to a proposal, based on its amount. For a
+        // recurring payment it is the total of all payments.
+        #[inline]
+        fn approval_tier(&self, proposal_id: ProposalId, proposal: &Proposal) -> ApprovalTier {
+            let amount = match self.recurring_schedules.get(proposal_id) {
+                Some(schedule) => proposal
+                    .amount
+                    .saturating_mul(Balance::from(schedule.periods)),
+                None => proposal.amount,
+            };
+            self.state
+                .config
+                .approval_tiers
+                .iter()
+                .filter(|tier| tier.min_amount <= amount)
+                .max_by_key(|tier| tier.min_amount)
+                .copied()
+                .unwrap_or(ApprovalTier {
+                    min_amount: 0,
+                    majority: Majority::Simple,
+                    quorum: self.state.quorum,
+                })
+        }
+
         #[inline]
-        fn quorum_met(&self, proposal_id: ProposalId) -> Result<u8> {
+        fn quorum_met(&self, proposal_id: ProposalId, quorum: u8) -> Result<u32> {
             let total_voters = self.total_voters.get(proposal_id).unwrap_or_default();
-            if total_voters < self.quorum {
+            let quorum_met = match self.state.voting_source {
+                VotingSource::GovernanceToken(_) | VotingSource::Erc1155Token(..) => {
+                    total_voters >= u32::from(quorum)
+                }
+                VotingSource::Membership => {
+                    u64::from(total_voters) * 100
+                        >= u64::from(quorum) * u64::from(self.state.total_members)
+                }
+            };
+            if !quorum_met {
                 return Err(Error::QuorumNotMet);
             }
             Ok(total_voters)
         }
 
         #[inline]
-        fn proposal_pass(&self, proposal_votes: ProposalVotes) -> Result<()> {
-            if proposal_votes.total_yes <= proposal_votes.total_no {
-                return Err(Error::ProposalRefused);
+        fn proposal_pass(
+            &self,
+            proposal: &Proposal,
+            majority: Majority,
+            proposal_votes: ProposalVotes,
+        ) -> Result<()> {
+            match proposal.kind {
+                ProposalKind::Standard => {
+                    if proposal_votes.total_yes <= proposal_votes.total_no {
+                        return Err(Error::ProposalRefused);
+                    }
+                    if majority == Majority::TwoThirds {
+                        let yes_share = proposal_votes
+                            .total_yes
+                            .checked_mul(3)
+                            .ok_or(Error::ArithmeticOverflow)?;
+                        let two_thirds = proposal_votes
+                            .total_yes
+                            .checked_add(proposal_votes.total_no)
+                            .and_then(|total| total.checked_mul(2))
+                            .ok_or(Error::ArithmeticOverflow)?;
+                        if yes_share < two_thirds {
+                            return Err(Error::SupermajorityNotReached);
+                        }
+                    }
+                }
+                ProposalKind::Optimistic => {
+                    if self.veto_threshold_reached(proposal_votes.total_no)? {
+                        return Err(Error::VetoThresholdReached);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn veto_threshold_reached(&self, total_no: Votes) -> Result<bool> {
+            // Without `No` votes there is no need to ask the governance token.
+            if total_no == 0 {
+                return Ok(self.state.veto_threshold == 0);
+            }
+            let no_share = total_no.checked_mul(100).ok_or(Error::ArithmeticOverflow)?;
+            let veto_share = self
+                .total_voting_power()
+                .checked_mul(Balance::from(self.state.veto_threshold))
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(no_share >= veto_share)
+        }
+
+        // Register `amount` as spent in the current epoch.
+        #[inline]
+        fn spend(&mut self, amount: Balance) -> Result<()> {
+            if let Some((epoch, spent)) = self.epoch_spent_after(amount)? {
+                self.epoch_spent.insert(epoch, &spent);
             }
             Ok(())
         }
 
+        // The current epoch and what would be spent in it after spending `amount`.
+        #[inline]
+        fn epoch_spent_after(&self, amount: Balance) -> Result<Option<(u64, Balance)>> {
+            let Some(spending_limit) = self.state.config.spending_limit else {
+                return Ok(None);
+            };
+            let epoch = self.current_epoch(&spending_limit);
+            let spent = self
+                .epoch_spent
+                .get(epoch)
+                .unwrap_or_default()
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            if spent > spending_limit.amount {
+                return Err(Error::SpendingLimitExceeded);
+            }
+            Ok(Some((epoch, spent)))
+        }
+
+        #[inline]
+        fn current_epoch(&self, spending_limit: &SpendingLimit) -> u64 {
+            self.env()
+                .block_timestamp()
+                .checked_div(spending_limit.epoch_duration.saturating_mul(DAYS))
+                .unwrap_or_default()
+        }
+
         #[inline]
         fn transfer_proposal_amount(&self, proposal: Proposal) -> Result<()> {
             if self.env().transfer(proposal.to, proposal.amount).is_err() {
@@ -389,121 +1918,1541 @@ mod dao {
             Ok(())
         }
 
-        #[ink(message, payable)]
-        pub fn fund(&self) {
-            let _from = self.env().caller();
-            let _amount = self.env().transferred_value();
-            // Self::env().emit_event(Fund{
-            //     from,
-            //     amount,
-            // });
+        #[inline]
+        fn add_member(&mut self, member: AccountId) -> Result<()> {
+            if self.is_member(member) {
+                return Err(Error::AlreadyMember);
+            }
+            self.members.insert(member, &());
+            self.state.total_members = self
+                .state
+                .total_members
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.emit_event(MemberAdded { member });
+            Ok(())
         }
 
-        #[ink(message)]
-        pub fn get_treasury_amount(&self) -> Balance {
-            self.env().balance()
+        #[inline]
+        fn remove_member(&mut self, member: AccountId) -> Result<()> {
+            if !self.is_member(member) {
+                return Err(Error::MemberNotFound);
+            }
+            self.members.remove(member);
+            self.state.total_members -= 1;
+            self.emit_event(MemberRemoved { member });
+            Ok(())
         }
 
-        // Get the information regarding a proposal.
-        #[ink(message)]
-        pub fn get_proposal(&self, proposal_id: ProposalId) -> Result<Proposal> {
-            self.proposals
-                .get(proposal_id)
-                .ok_or(Error::ProposalNotFound)
+        #[inline]
+        fn add_guardian(&mut self, guardian: AccountId) -> Result<()> {
+            if self.is_guardian(guardian) {
+                return Err(Error::AlreadyGuardian);
+            }
+            self.state.guardians.push(guardian);
+            self.emit_event(GuardianAdded { guardian });
+            Ok(())
         }
 
-        // Get the total votes regarding a proposal.
+        #[inline]
+        fn remove_guardian(&mut self, guardian: AccountId) -> Result<()> {
+            if !self.is_guardian(guardian) {
+                return Err(Error::GuardianNotFound);
+            }
+            self.state.guardians.retain(|account| *account != guardian);
+            self.emit_event(GuardianRemoved { guardian });
+            Ok(())
+        }
+
+        #[inline]
+        fn is_guardian(&self, account: AccountId) -> bool {
+            self.state.guardians.contains(&account)
+        }
+
+        // Veto a succeeded proposal as a guardian. The proposal can not be executed
+        // anymore once the majority of the guardians vetoed it.
         #[ink(message)]
-        pub fn get_votes(&self, proposal_id: ProposalId) -> Result<ProposalVotes> {
-            self.proposal_votes
-                .get(proposal_id)
-                .ok_or(Error::ProposalNotFound)
+        pub fn veto(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.lock()?;
+            let result = self.cast_veto(proposal_id);
+            self.unlock();
+            result
         }
 
-        // Get the number of voters that voted on a proposal
+        #[inline]
+        fn cast_veto(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.is_guardian(caller) {
+                return Err(Error::NotGuardian);
+            }
+            let mut proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                _ => return Err(Error::ProposalNotFound),
+            };
+            self.proposal_executed(&proposal)?;
+            self.proposal_vetoed(&proposal)?;
+            self.proposal_succeeded(proposal_id, &proposal)?;
+            if self.guardian_vetoes.get((proposal_id, caller)).is_some() {
+                return Err(Error::AlreadyVetoed);
+            }
+            self.guardian_vetoes.insert((&proposal_id, &caller), &());
+            self.emit_event(VetoCast {
+                proposal_id,
+                guardian: caller,
+            });
+            let total_vetoes = self.get_vetoes(proposal_id)?;
+            if usize::from(total_vetoes) * 2 > self.state.guardians.len() {
+                proposal.vetoed = true;
+                self.proposals.insert(proposal_id, &proposal);
+                self.emit_event(ProposalVetoed {
+                    proposal_id,
+                    total_vetoes,
+                });
+            }
+            Ok(())
+        }
+
+        // Leave the DAO with a share of the treasury. The caller's `amount` of governance
+        // tokens, which the DAO must be allowed to transfer, are burned in exchange for
+        // `amount / total_supply` of the treasury.
         #[ink(message)]
-        pub fn get_voters(&self, proposal_id: ProposalId) -> Result<u8> {
-            self.total_voters
-                .get(proposal_id)
-                .ok_or(Error::ProposalNotFound)
+        pub fn ragequit(&mut self, amount: Balance) -> Result<()> {
+            self.lock()?;
+            let result = self.ragequit_share(amount);
+            self.unlock();
+            result
         }
 
-        // Get the amount of time left to vote on a proposal.
+        #[inline]
+        fn ragequit_share(&mut self, amount: Balance) -> Result<()> {
+            if amount == 0 {
+                return Err(Error::InvalidRagequitAmount);
+            }
+            let governance_token = self.governance_token()?;
+            let caller = self.env().caller();
+            let total_supply = self.total_supply(governance_token);
+            let share = self
+                .treasury_balance()
+                .checked_mul(amount)
+                .ok_or(Error::ArithmeticOverflow)?
+                .checked_div(total_supply)
+                .ok_or(Error::InsufficientBalance)?;
+            self.transfer_governance_tokens(governance_token, caller, amount)?;
+            self.burn_governance_tokens(governance_token, amount)?;
+            if self.env().transfer(caller, share).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            self.emit_event(Ragequit {
+                member: caller,
+                amount,
+                share,
+            });
+            Ok(())
+        }
+
+        // Withdraw the governance tokens escrowed for a vote, once the proposal ended.
         #[ink(message)]
-        pub fn get_proposal_end(&self, proposal_id: ProposalId) -> Result<Timestamp> {
+        pub fn withdraw_vote_tokens(&mut self, proposal_id: ProposalId) -> Result<()> {
+            self.lock()?;
+            let result = self.withdraw_escrow(proposal_id);
+            self.unlock();
+            result
+        }
+
+        #[inline]
+        fn withdraw_escrow(&mut self, proposal_id: ProposalId) -> Result<()> {
+            if !self.proposals.contains(proposal_id) {
+                return Err(Error::ProposalNotFound);
+            }
+            // Carried over votes stay backed until the last amendment carrying them ends.
             let proposal = self
                 .proposals
-                .get(proposal_id)
-                .ok_or(Error::ProposalNotFound)?;
-            Ok(proposal.end)
+                .get(self.last_carrier(proposal_id))
+                .unwrap_or_else(|| panic!("amendments are proposals"));
+            if !self.proposal_expired(&proposal) {
+                return Err(Error::ProposalStillActive);
+            }
+            let caller = self.env().caller();
+            let amount = self
+                .escrows
+                .get((proposal_id, caller))
+                .ok_or(Error::NoEscrowedTokens)?;
+            self.escrows.remove((proposal_id, caller));
+            self.release_vote_tokens(caller, amount)?;
+            self.emit_event(VoteTokensWithdrawn {
+                proposal_id,
+                voter: caller,
+                amount,
+            });
+            Ok(())
         }
 
-        // #[inline]
-        // fn total_supply(&self) -> Balance {
-        //     build_call::<DefaultEnvironment>()
-        //         .call(self.governance_token)
-        //         .gas_limit(0)
-        //         .transferred_value(0)
-        //         .call_flags(CallFlags::default())
-        //         .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
-        //             "total_supply"
-        //         ))))
-        //         .returns::<Balance>()
-        //         .try_invoke()
-        //         .unwrap_or_else(|env_err| {
-        //             panic!("cross-contract call to erc20 failed due to {:?}", env_err)
-        //         })
-        //         .unwrap_or_else(|lang_err| {
-        //             panic!("cross-contract call to erc20 failed due to {:?}", lang_err)
-        //         })
-        // }
+        // Get the governance tokens a voter has escrowed for a proposal.
+        #[ink(message)]
+        pub fn get_escrow(&self, proposal_id: ProposalId, voter: AccountId) -> Balance {
+            self.escrows.get((proposal_id, voter)).unwrap_or_default()
+        }
+
+        // Pull the tokens a vote is weighted with, of the governance token or of the
+        // ERC-1155 token id, from the voter into escrow.
+        #[inline]
+        fn escrow_vote_tokens(&self, voter: AccountId, amount: Balance) -> Result<()> {
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(governance_token) => {
+                    self.transfer_governance_tokens(governance_token, voter, amount)
+                }
+                VotingSource::Erc1155Token(erc1155, token_id) => self.transfer_erc1155_tokens(
+                    erc1155,
+                    token_id,
+                    voter,
+                    self.env().account_id(),
+                    amount,
+                ),
+                VotingSource::Membership => Err(Error::NoGovernanceToken),
+            }
+        }
+
+        // Send escrowed vote tokens back to the voter.
+        #[inline]
+        fn release_vote_tokens(&self, voter: AccountId, amount: Balance) -> Result<()> {
+            match self.state.voting_source {
+                VotingSource::GovernanceToken(governance_token) => {
+                    self.send_governance_tokens(governance_token, voter, amount)
+                }
+                VotingSource::Erc1155Token(erc1155, token_id) => self.transfer_erc1155_tokens(
+                    erc1155,
+                    token_id,
+                    self.env().account_id(),
+                    voter,
+                    amount,
+                ),
+                VotingSource::Membership => Err(Error::NoGovernanceToken),
+            }
+        }
+
+        // Move ERC-1155 tokens of `from`, which is either the DAO or has approved the
+        // DAO as its operator.
+        #[inline]
+        fn transfer_erc1155_tokens(
+            &self,
+            erc1155: AccountId,
+            token_id: TokenId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let mut token: contract_ref!(Erc1155) = erc1155.into();
+            let transferred = matches!(
+                token
+                    .call_mut()
+                    .safe_transfer_from(from, to, token_id, amount, Vec::new())
+                    .try_invoke(),
+                Ok(Ok(Ok(())))
+            );
+            if !transferred {
+                return Err(Error::TokenTransferFailed);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn transfer_governance_tokens(
+            &self,
+            governance_token: AccountId,
+            from: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let to = self.env().account_id();
+            let transferred = match self.state.config.token_interface {
+                TokenInterface::Erc20 => {
+                    let mut token: contract_ref!(Erc20) = governance_token.into();
+                    matches!(
+                        token
+                            .call_mut()
+                            .transfer_from(from, to, amount)
+                            .try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+                TokenInterface::Psp22 => {
+                    let mut token: contract_ref!(PSP22) = governance_token.into();
+                    matches!(
+                        token
+                            .call_mut()
+                            .transfer_from(from, to, amount, Vec::new())
+                            .try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+            };
+            if !transferred {
+                return Err(Error::TokenTransferFailed);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn send_governance_tokens(
+            &self,
+            governance_token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let transferred = match self.state.config.token_interface {
+                TokenInterface::Erc20 => {
+                    let mut token: contract_ref!(Erc20) = governance_token.into();
+                    matches!(
+                        token.call_mut().transfer(to, amount).try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+                TokenInterface::Psp22 => {
+                    let mut token: contract_ref!(PSP22) = governance_token.into();
+                    matches!(
+                        token
+                            .call_mut()
+                            .transfer(to, amount, Vec::new())
+                            .try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+            };
+            if !transferred {
+                return Err(Error::TokenTransferFailed);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn burn_governance_tokens(
+            &self,
+            governance_token: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let burned = match self.state.config.token_interface {
+                TokenInterface::Erc20 => {
+                    let mut token: contract_ref!(Erc20Burnable) = governance_token.into();
+                    matches!(
+                        token
+                            .call_mut()
+                            .burn(self.env().account_id(), amount)
+                            .try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+                TokenInterface::Psp22 => {
+                    let mut token: contract_ref!(PSP22Burnable) = governance_token.into();
+                    matches!(
+                        token
+                            .call_mut()
+                            .burn(self.env().account_id(), amount)
+                            .try_invoke(),
+                        Ok(Ok(Ok(())))
+                    )
+                }
+            };
+            if !burned {
+                return Err(Error::TokenBurnFailed);
+            }
+            Ok(())
+        }
+
+        // The events of the contracts the DAO depends on make `self.env().emit_event`
+        // ambiguous.
+        #[inline]
+        fn emit_event<E>(&self, event: E)
+        where
+            E: Into<<Dao as ContractEventBase>::Type>,
+        {
+            <ink::EnvAccess<'_, DefaultEnvironment> as EmitEvent<Dao>>::emit_event(
+                self.env(),
+                event,
+            )
+        }
+
+        // Move the voting reward for a proposal from the reward pool to the reserved
+        // rewards. Only proposals that are voted on get a reward.
+        #[inline]
+        fn reserve_voting_reward(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let reward = core::cmp::min(self.state.config.voting_reward, self.state.reward_pool);
+            if reward == 0 {
+                return Ok(());
+            }
+            self.state.reward_pool -= reward;
+            self.state.reserved_rewards = self
+                .state
+                .reserved_rewards
+                .checked_add(reward)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.proposal_rewards.insert(proposal_id, &reward);
+            self.unclaimed_rewards.insert(proposal_id, &reward);
+            Ok(())
+        }
+
+        // Move the unclaimed voting reward of a proposal back to the reward pool.
+        #[inline]
+        fn release_voting_reward(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let unclaimed = self.unclaimed_rewards.get(proposal_id).unwrap_or_default();
+            self.unclaimed_rewards.remove(proposal_id);
+            self.state.reserved_rewards -= unclaimed;
+            self.state.reward_pool = self
+                .state
+                .reward_pool
+                .checked_add(unclaimed)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(())
+        }
+
+        // Claim the share of the voting reward of a proposal, in proportion to the
+        // caller's voting power, once voting has ended. Superseded proposals pay no
+        // rewards.
+        #[ink(message)]
+        pub fn claim_voting_reward(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                _ => return Err(Error::ProposalNotFound),
+            };
+            self.proposal_superseded(proposal_id)?;
+            if !self.proposal_expired(&proposal) {
+                return Err(Error::ProposalStillActive);
+            }
+            let caller = self.env().caller();
+            let voting_power = self
+                .vote_receipt(proposal_id, caller)
+                .ok_or(Error::NotVoted)?
+                .weight;
+            if self.claimed_rewards.get((proposal_id, caller)).is_some() {
+                return Err(Error::RewardAlreadyClaimed);
+            }
+            let proposal_reward = self
+                .proposal_rewards
+                .get(proposal_id)
+                .ok_or(Error::NoVotingReward)?;
+            let proposal_votes = self.get_votes(proposal_id)?;
+            let total_votes = proposal_votes
+                .total_yes
+                .checked_add(proposal_votes.total_no)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let reward = proposal_reward
+                .checked_mul(voting_power)
+                .ok_or(Error::ArithmeticOverflow)?
+                / total_votes;
+            let unclaimed = self
+                .unclaimed_rewards
+                .get(proposal_id)
+                .unwrap_or_default()
+                .checked_sub(reward)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let reward_claimants = self
+                .reward_claimants
+                .get(proposal_id)
+                .unwrap_or_default()
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.claimed_rewards.insert((&proposal_id, &caller), &());
+            self.reward_claimants.insert(proposal_id, &reward_claimants);
+            self.unclaimed_rewards.insert(proposal_id, &unclaimed);
+            self.state.reserved_rewards -= reward;
+            // The rounding dust goes back to the reward pool once every voter claimed.
+            if Some(reward_claimants) == self.total_voters.get(proposal_id) {
+                self.release_voting_reward(proposal_id)?;
+            }
+            if self.env().transfer(caller, reward).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            self.emit_event(VotingRewardClaimed {
+                proposal_id,
+                voter: caller,
+                reward,
+            });
+            Ok(())
+        }
+
+        // Fund the reward pool for voting rewards.
+        #[ink(message, payable)]
+        pub fn fund_rewards(&mut self) -> Result<()> {
+            self.state.reward_pool = self
+                .state
+                .reward_pool
+                .checked_add(self.env().transferred_value())
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(())
+        }
+
+        // Get the balance of the reward pool.
+        #[ink(message)]
+        pub fn get_reward_pool(&self) -> Balance {
+            self.state.reward_pool
+        }
+
+        // Get the voting reward reserved for a proposal.
+        #[ink(message)]
+        pub fn get_proposal_reward(&self, proposal_id: ProposalId) -> Result<Balance> {
+            if self.proposals.get(proposal_id).is_none() {
+                return Err(Error::ProposalNotFound);
+            }
+            Ok(self.proposal_rewards.get(proposal_id).unwrap_or_default())
+        }
+
+        #[inline]
+        fn create_committee(&mut self, proposal_id: ProposalId, allocation: Balance) -> Result<()> {
+            let spec = self
+                .committee_specs
+                .get(proposal_id)
+                .expect("committee proposals store the spec of their committee");
+            let committee_id = self.state.next_committee_id;
+            self.state.next_committee_id = committee_id
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let mut total_members = 0;
+            for member in spec.members {
+                if self.committee_members.get((committee_id, member)).is_none() {
+                    self.committee_members.insert((&committee_id, &member), &());
+                    total_members += 1;
+                }
+            }
+            self.committees.insert(
+                committee_id,
+                &Committee {
+                    allocation,
+                    budget: spec.budget,
+                    quorum: spec.quorum,
+                    voting_duration: spec.voting_duration,
+                    total_members,
+                    next_spend_id: 0,
+                },
+            );
+            self.state.committee_allocations = self
+                .state
+                .committee_allocations
+                .checked_add(allocation)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.emit_event(CommitteeCreated {
+                committee_id,
+                allocation,
+            });
+            Ok(())
+        }
+
+        #[inline]
+        fn clawback_committee(&mut self, proposal_id: ProposalId, amount: Balance) -> Result<()> {
+            let committee_id = self.proposal_committee(proposal_id);
+            let mut committee = self.get_committee(committee_id)?;
+            let amount = core::cmp::min(amount, committee.allocation);
+            committee.allocation -= amount;
+            self.state.committee_allocations -= amount;
+            self.committees.insert(committee_id, &committee);
+            Ok(())
+        }
+
+        #[inline]
+        fn dissolve_committee(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let committee_id = self.proposal_committee(proposal_id);
+            let committee = self.get_committee(committee_id)?;
+            self.state.committee_allocations -= committee.allocation;
+            self.committees.remove(committee_id);
+            self.emit_event(CommitteeDissolved {
+                committee_id,
+                returned: committee.allocation,
+            });
+            Ok(())
+        }
+
+        #[inline]
+        fn proposal_committee(&self, proposal_id: ProposalId) -> CommitteeId {
+            self.committee_proposals
+                .get(proposal_id)
+                .expect("clawback and dissolve proposals store their committee")
+        }
+
+        #[inline]
+        fn committee_member(&self, committee_id: CommitteeId) -> Result<()> {
+            if !self.is_committee_member(committee_id, self.env().caller()) {
+                return Err(Error::NotCommitteeMember);
+            }
+            Ok(())
+        }
+
+        // Propose to spend `amount` of a committee's allocation as a member of the
+        // committee. The proposer approves the spend.
+        #[ink(message)]
+        pub fn committee_propose(
+            &mut self,
+            committee_id: CommitteeId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            let mut committee = self.get_committee(committee_id)?;
+            self.committee_member(committee_id)?;
+            if amount == 0 {
+                return Err(Error::InvalidProposalAmount);
+            }
+            if amount > committee.allocation {
+                return Err(Error::InsufficientCommitteeAllocation);
+            }
+            let spend_id = committee.next_spend_id;
+            committee.next_spend_id = spend_id.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            self.committees.insert(committee_id, &committee);
+            self.committee_spends.insert(
+                (committee_id, spend_id),
+                &CommitteeSpend {
+                    to,
+                    amount,
+                    end: self.env().block_timestamp() + (committee.voting_duration * DAYS),
+                    approvals: 0,
+                    executed: false,
+                },
+            );
+            self.committee_approve(committee_id, spend_id)
+        }
+
+        // Approve a spend as a member of the committee.
+        #[ink(message)]
+        pub fn committee_approve(
+            &mut self,
+            committee_id: CommitteeId,
+            spend_id: SpendId,
+        ) -> Result<()> {
+            self.get_committee(committee_id)?;
+            self.committee_member(committee_id)?;
+            let mut spend = self.get_committee_spend(committee_id, spend_id)?;
+            self.committee_spend_open(&spend)?;
+            let caller = self.env().caller();
+            if self
+                .committee_approvals
+                .get((committee_id, spend_id, caller))
+                .is_some()
+            {
+                return Err(Error::AlreadyApproved);
+            }
+            spend.approvals = spend
+                .approvals
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.committee_approvals
+                .insert((&committee_id, &spend_id, &caller), &());
+            self.committee_spends
+                .insert((committee_id, spend_id), &spend);
+            Ok(())
+        }
+
+        // Execute a spend once enough members of the committee approved it.
+        #[ink(message)]
+        pub fn committee_execute(
+            &mut self,
+            committee_id: CommitteeId,
+            spend_id: SpendId,
+        ) -> Result<()> {
+            self.lock()?;
+            let result = self.execute_committee_spend(committee_id, spend_id);
+            self.unlock();
+            result
+        }
+
+        #[inline]
+        fn execute_committee_spend(
+            &mut self,
+            committee_id: CommitteeId,
+            spend_id: SpendId,
+        ) -> Result<()> {
+            // Checks
+            let mut committee = self.get_committee(committee_id)?;
+            let mut spend = self.get_committee_spend(committee_id, spend_id)?;
+            self.committee_spend_open(&spend)?;
+            if u64::from(spend.approvals) * 100
+                < u64::from(committee.quorum) * u64::from(committee.total_members)
+            {
+                return Err(Error::CommitteeQuorumNotMet);
+            }
+            if spend.amount > committee.allocation {
+                return Err(Error::InsufficientCommitteeAllocation);
+            }
+            let epoch = self.current_epoch(&committee.budget);
+            let spent = self
+                .committee_epoch_spent
+                .get((committee_id, epoch))
+                .unwrap_or_default()
+                .checked_add(spend.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            if spent > committee.budget.amount {
+                return Err(Error::SpendingLimitExceeded);
+            }
+            // Effects
+            self.committee_epoch_spent
+                .insert((committee_id, epoch), &spent);
+            committee.allocation -= spend.amount;
+            self.state.committee_allocations -= spend.amount;
+            self.committees.insert(committee_id, &committee);
+            spend.executed = true;
+            self.committee_spends
+                .insert((committee_id, spend_id), &spend);
+            // Interactions
+            if self.env().transfer(spend.to, spend.amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn committee_spend_open(&self, spend: &CommitteeSpend) -> Result<()> {
+            if spend.executed {
+                return Err(Error::ProposalExecuted);
+            }
+            if self.env().block_timestamp() >= spend.end {
+                return Err(Error::ProposalExpired);
+            }
+            Ok(())
+        }
+
+        #[inline]
+        fn create_recurring_payment(&mut self, proposal_id: ProposalId, proposal: &Proposal) {
+            let schedule = self
+                .recurring_schedules
+                .get(proposal_id)
+                .expect("recurring payment proposals store their schedule");
+            // The first payment can be claimed right away.
+            self.recurring_payments.insert(
+                proposal_id,
+                &RecurringPayment {
+                    to: proposal.to,
+                    amount: proposal.amount,
+                    interval: schedule.interval,
+                    payments_left: schedule.periods,
+                    next_claim: self.env().block_timestamp(),
+                },
+            );
+        }
+
+        #[inline]
+        fn revoke_recurring_payment(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let payment_id = self.revoked_payment(proposal_id);
+            self.get_recurring_payment(payment_id)?;
+            self.recurring_payments.remove(payment_id);
+            Ok(())
+        }
+
+        #[inline]
+        fn revoked_payment(&self, proposal_id: ProposalId) -> ProposalId {
+            self.revoke_proposals
+                .get(proposal_id)
+                .expect("revoke proposals store the recurring payment they revoke")
+        }
+
+        // Claim the next payment of a recurring payment as its recipient. Missed
+        // payments can be claimed one after the other.
+        #[ink(message)]
+        pub fn claim_recurring_payment(&mut self, payment_id: ProposalId) -> Result<()> {
+            self.lock()?;
+            let result = self.pay_recurring_payment(payment_id);
+            self.unlock();
+            result
+        }
+
+        #[inline]
+        fn pay_recurring_payment(&mut self, payment_id: ProposalId) -> Result<()> {
+            // Checks
+            let mut payment = self.get_recurring_payment(payment_id)?;
+            if self.env().caller() != payment.to {
+                return Err(Error::NotRecipient);
+            }
+            if self.env().block_timestamp() < payment.next_claim {
+                return Err(Error::PaymentNotDue);
+            }
+            if payment.amount > self.treasury_balance() {
+                return Err(Error::InsufficientTreasuryBalance);
+            }
+            // Effects
+            self.spend(payment.amount)?;
+            payment.payments_left -= 1;
+            if payment.payments_left == 0 {
+                self.recurring_payments.remove(payment_id);
+            } else {
+                payment.next_claim = payment
+                    .interval
+                    .checked_mul(DAYS)
+                    .and_then(|interval| payment.next_claim.checked_add(interval))
+                    .ok_or(Error::ArithmeticOverflow)?;
+                self.recurring_payments.insert(payment_id, &payment);
+            }
+            // Interactions
+            if self.env().transfer(payment.to, payment.amount).is_err() {
+                return Err(Error::TransferFailed);
+            }
+            self.emit_event(RecurringPaymentClaimed {
+                payment_id,
+                to: payment.to,
+                amount: payment.amount,
+                payments_left: payment.payments_left,
+            });
+            Ok(())
+        }
+
+        // Get a recurring payment by the id of the proposal that created it.
+        #[ink(message)]
+        pub fn get_recurring_payment(&self, payment_id: ProposalId) -> Result<RecurringPayment> {
+            self.recurring_payments
+                .get(payment_id)
+                .ok_or(Error::RecurringPaymentNotFound)
+        }
+
+        // Get the time from which the next payment of a recurring payment can be claimed.
+        #[ink(message)]
+        pub fn get_next_claim(&self, payment_id: ProposalId) -> Result<Timestamp> {
+            Ok(self.get_recurring_payment(payment_id)?.next_claim)
+        }
+
+        // Get the number of payments left of a recurring payment: all periods of the
+        // schedule until the proposal is executed, 0 once it has been paid out or revoked.
+        #[ink(message)]
+        pub fn get_payments_left(&self, payment_id: ProposalId) -> Result<u32> {
+            let schedule = match self.recurring_schedules.get(payment_id) {
+                Some(schedule) => schedule,
+                _ => return Err(Error::RecurringPaymentNotFound),
+            };
+            if let Some(payment) = self.recurring_payments.get(payment_id) {
+                return Ok(payment.payments_left);
+            }
+            let executed = self
+                .proposals
+                .get(payment_id)
+                .map(|proposal| proposal.executed)
+                .unwrap_or_default();
+            Ok(if executed { 0 } else { schedule.periods })
+        }
+
+        // Get a committee.
+        #[ink(message)]
+        pub fn get_committee(&self, committee_id: CommitteeId) -> Result<Committee> {
+            self.committees
+                .get(committee_id)
+                .ok_or(Error::CommitteeNotFound)
+        }
+
+        // Get a spend of a committee.
+        #[ink(message)]
+        pub fn get_committee_spend(
+            &self,
+            committee_id: CommitteeId,
+            spend_id: SpendId,
+        ) -> Result<CommitteeSpend> {
+            self.committee_spends
+                .get((committee_id, spend_id))
+                .ok_or(Error::SpendNotFound)
+        }
+
+        #[ink(message)]
+        pub fn is_committee_member(&self, committee_id: CommitteeId, account: AccountId) -> bool {
+            self.committees.contains(committee_id)
+                && self.committee_members.contains((committee_id, account))
+        }
+
+        // Fund the treasury, the contribution is recorded for the caller.
+        #[ink(message, payable)]
+        pub fn fund(&mut self) -> Result<()> {
+            let from = self.env().caller();
+            let amount = self.env().transferred_value();
+            if amount == 0 && self.state.config.reject_zero_funding {
+                return Err(Error::ZeroFunding);
+            }
+            self.contribute(from, amount)
+        }
+
+        #[inline]
+        fn contribute(&mut self, from: AccountId, amount: Balance) -> Result<()> {
+            let contribution = self
+                .contributions
+                .get(from)
+                .unwrap_or_default()
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.state.total_inflow = self
+                .state
+                .total_inflow
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.contributions.insert(from, &contribution);
+            self.emit_event(Fund { from, amount });
+            Ok(())
+        }
+
+        // Get the total value an account funded the treasury with.
+        #[ink(message)]
+        pub fn get_contribution(&self, account: AccountId) -> Balance {
+            self.contributions.get(account).unwrap_or_default()
+        }
+
+        // Get the total value the treasury has been funded with.
+        #[ink(message)]
+        pub fn get_total_inflow(&self) -> Balance {
+            self.state.total_inflow
+        }
+
+        #[ink(message)]
+        pub fn get_treasury_amount(&self) -> Balance {
+            self.treasury_balance()
+        }
+
+        // The contract balance minus the voting rewards and committee allocations.
+        #[inline]
+        fn treasury_balance(&self) -> Balance {
+            self.env()
+                .balance()
+                .saturating_sub(self.state.reward_pool)
+                .saturating_sub(self.state.reserved_rewards)
+                .saturating_sub(self.state.committee_allocations)
+        }
+
+        // The code takes effect from the next call, which is `fn migrate` of the new code.
+        #[inline]
+        fn set_code_hash(&mut self, proposal_id: ProposalId) -> Result<()> {
+            let code_hash = self
+                .code_hashes
+                .get(proposal_id)
+                .expect("set code hash proposals store their code hash");
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::CodeNotFound)
+        }
+
+        // Move the storage forward to the current storage version and return it. Anyone
+        // can migrate: the code, and so the steps that run, is set by governance (see
+        // `fn propose_set_code_hash`) or, for a DAO from before versioning, by the chain.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<u32> {
+            let version = self.get_storage_version();
+            if version >= STORAGE_VERSION {
+                return Err(Error::AlreadyMigrated);
+            }
+            if version == 0 {
+                self.migrate_unversioned();
+            }
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(STORAGE_VERSION)
+        }
+
+        // Before versioning a proposal had no kind, action or veto flag and the number
+        // of voters was a `u8`. The storage root is converted by `DaoState` and the
+        // votes of that time have no receipt.
+        #[inline]
+        fn migrate_unversioned(&mut self) {
+            let unversioned_proposals: Mapping<
+                ProposalId,
+                UnversionedProposal,
+                ManualKey<UNVERSIONED_PROPOSALS_KEY>,
+            > = Mapping::default();
+            let unversioned_total_voters: Mapping<
+                ProposalId,
+                u8,
+                ManualKey<UNVERSIONED_TOTAL_VOTERS_KEY>,
+            > = Mapping::default();
+            for proposal_id in 1..=self.next_proposal_id {
+                if let Some(proposal) = unversioned_proposals.get(proposal_id) {
+                    self.proposals.insert(
+                        proposal_id,
+                        &Proposal {
+                            to: proposal.to,
+                            amount: proposal.amount,
+                            start: proposal.start,
+                            end: proposal.end,
+                            executed: proposal.executed,
+                            kind: ProposalKind::Standard,
+                            action: ProposalAction::Transfer,
+                            vetoed: false,
+                        },
+                    );
+                }
+                if let Some(total_voters) = unversioned_total_voters.get(proposal_id) {
+                    self.total_voters
+                        .insert(proposal_id, &u32::from(total_voters));
+                }
+            }
+        }
+
+        // Get the version of the storage layout.
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+
+        // Get the information regarding a proposal.
+        #[ink(message)]
+        pub fn get_proposal(&self, proposal_id: ProposalId) -> Result<Proposal> {
+            self.proposals
+                .get(proposal_id)
+                .ok_or(Error::ProposalNotFound)
+        }
+
+        // Get the state of a proposal.
+        #[ink(message)]
+        pub fn get_proposal_state(&self, proposal_id: ProposalId) -> Result<ProposalState> {
+            let proposal = match self.proposals.get(proposal_id) {
+                Some(proposal) => proposal,
+                _ => return Err(Error::ProposalNotFound),
+            };
+            let state = if proposal.executed {
+                ProposalState::Executed
+            } else if proposal.vetoed {
+                ProposalState::Vetoed
+            } else if self.amendments.contains(proposal_id) {
+                ProposalState::Superseded
+            } else if self.proposal_pending(&proposal) {
+                ProposalState::Pending
+            } else if !self.proposal_expired(&proposal) {
+                ProposalState::Active
+            } else if self.proposal_succeeded(proposal_id, &proposal).is_ok() {
+                ProposalState::Succeeded
+            } else {
+                ProposalState::Defeated
+            };
+            Ok(state)
+        }
+
+        // Get the total votes regarding a proposal.
+        #[ink(message)]
+        pub fn get_votes(&self, proposal_id: ProposalId) -> Result<ProposalVotes> {
+            self.proposal_votes
+                .get(proposal_id)
+                .ok_or(Error::ProposalNotFound)
+        }
+
+        // Get the approval tier (majority and quorum) that applies to a proposal.
+        #[ink(message)]
+        pub fn get_approval_tier(&self, proposal_id: ProposalId) -> Result<ApprovalTier> {
+            match self.proposals.get(proposal_id) {
+                Some(proposal) => Ok(self.approval_tier(proposal_id, &proposal)),
+                _ => Err(Error::ProposalNotFound),
+            }
+        }
+
+        // Get the receipt of the vote of an account that counts for a proposal.
+        #[ink(message)]
+        pub fn get_vote(&self, proposal_id: ProposalId, voter: AccountId) -> Option<VoteReceipt> {
+            self.vote_receipt(proposal_id, voter)
+        }
+
+        // Get the number of voters that voted on a proposal
+        #[ink(message)]
+        pub fn get_voters(&self, proposal_id: ProposalId) -> Result<u32> {
+            self.total_voters
+                .get(proposal_id)
+                .ok_or(Error::ProposalNotFound)
+        }
+
+        // Whether an account is a member of a membership based DAO.
+        #[ink(message)]
+        pub fn is_member(&self, account: AccountId) -> bool {
+            self.members.get(account).is_some()
+        }
+
+        // Get the number of members of a membership based DAO.
+        #[ink(message)]
+        pub fn get_total_members(&self) -> u32 {
+            self.state.total_members
+        }
+
+        // Get the value spent by the treasury in the current epoch.
+        #[ink(message)]
+        pub fn get_epoch_spent(&self) -> Balance {
+            match self.state.config.spending_limit {
+                Some(spending_limit) => self
+                    .epoch_spent
+                    .get(self.current_epoch(&spending_limit))
+                    .unwrap_or_default(),
+                None => 0,
+            }
+        }
+
+        // Get the value the treasury can still spend in the current epoch, `None` when
+        // there is no spending limit.
+        #[ink(message)]
+        pub fn get_epoch_remaining(&self) -> Option<Balance> {
+            self.state
+                .config
+                .spending_limit
+                .map(|spending_limit| spending_limit.amount.saturating_sub(self.get_epoch_spent()))
+        }
+
+        // Get the guardians of the DAO.
+        #[ink(message)]
+        pub fn get_guardians(&self) -> Vec<AccountId> {
+            self.state.guardians.clone()
+        }
+
+        // Get the number of current guardians that vetoed a proposal. Vetoes of
+        // guardians that have been removed since don't count.
+        #[ink(message)]
+        pub fn get_vetoes(&self, proposal_id: ProposalId) -> Result<u8> {
+            if self.proposals.get(proposal_id).is_none() {
+                return Err(Error::ProposalNotFound);
+            }
+            let total_vetoes = self
+                .state
+                .guardians
+                .iter()
+                .filter(|guardian| self.guardian_vetoes.contains((proposal_id, **guardian)))
+                .count();
+            u8::try_from(total_vetoes).map_err(|_| Error::ArithmeticOverflow)
+        }
+
+        // Get the amount of time left to vote on a proposal.
+        #[ink(message)]
+        pub fn get_proposal_end(&self, proposal_id: ProposalId) -> Result<Timestamp> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(proposal.end)
+        }
     }
 
-    #[cfg(all(test, feature = "e2e-tests"))]
-    mod e2e_tests {
-        use super::*;
-        use erc20::Erc20Ref;
-        use ink_e2e::build_message;
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use erc20::Erc20Ref;
+        use erc_1155::Erc1155Ref;
+        use ink_e2e::build_message;
+        use psp22_token::Psp22TokenRef;
+        use reentrancy_attacker::{ReentrancyAttackerRef, Reentry};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn gets_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate dao contract
+            let governance_token: AccountId = [0x08; 32].into();
+            let quorum = 10;
+            let dao_constructor =
+                DaoRef::new(governance_token, quorum, 20, Vec::new(), Config::default());
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Get treasury balance
+            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_treasury_amount());
+            let get_treasury_amount_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+                .await;
+            assert_eq!(get_treasury_amount_result.return_value(), 100);
+            // Test get_* without a proposal:
+            //
+            // Get end of proposal
+            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_proposal_end(1));
+            let get_end_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
+                .await;
+            assert_eq!(get_end_result.return_value(), Err(Error::ProposalNotFound));
+            // Get proposal
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert_eq!(
+                get_proposal_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
+            // Get total votes on proposal
+            let get_votes =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
+            let get_votes_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+                .await;
+            assert_eq!(
+                get_votes_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
+            // Test with a proposal:
+            //
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let _propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await
+                .expect("Proposal failed");
+            // Get end of proposal
+            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_proposal_end(1));
+            let get_end_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
+                .await;
+            assert!(get_end_result.exec_result.result.is_ok());
+            // Hacky way due to no timestamp() in e2e_tests yet
+            let start = get_end_result
+                .return_value()
+                .unwrap_or_else(|_| panic!("shouldn't panic"))
+                - (10 * DAYS);
+            // Get proposal
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert_eq!(
+                get_proposal_result.return_value(),
+                Ok(Proposal {
+                    to: ferdie_account,
+                    amount: 10,
+                    start,
+                    end: start + (10 * DAYS),
+                    executed: false,
+                    kind: ProposalKind::Standard,
+                    action: ProposalAction::Transfer,
+                    vetoed: false,
+                })
+            );
+            // Get total voters on proposal
+            let get_voters =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
+            let get_voters_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+                .await;
+            assert_eq!(get_voters_result.return_value(), Ok(0));
+
+            // Get total votes on proposal
+            let get_votes =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
+            let get_votes_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+                .await;
+            assert_eq!(
+                get_votes_result.return_value(),
+                Ok(ProposalVotes {
+                    total_yes: 0,
+                    total_no: 0,
+                })
+            );
+
+            // Get total voters on proposal
+            let get_voters =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
+            let get_voters_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+                .await;
+            assert_eq!(get_voters_result.return_value(), Ok(0));
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn incorrect_proposals(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate dao contract
+            let governance_token: AccountId = [0x08; 32].into();
+            let quorum = 10;
+            let dao_constructor =
+                DaoRef::new(governance_token, quorum, 20, Vec::new(), Config::default());
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Get treasury balance
+            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_treasury_amount());
+            let get_treasury_amount_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+                .await;
+            assert_eq!(get_treasury_amount_result.return_value(), 100);
+            // Propose an incorrect proposals
+            //
+            // Invalid proposal amount (Error::InvalidProposalAmount)
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 0, 10));
+            let propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await;
+            assert!(propose_result.is_err());
+            // Invalid proposal duration (Error::InvalidProposalDuration)
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 0));
+            let propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await;
+            assert!(propose_result.is_err());
+
+            // Get checks
+            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_proposal_end(1));
+            let get_end_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
+                .await;
+            assert_eq!(get_end_result.return_value(), Err(Error::ProposalNotFound));
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert_eq!(
+                get_proposal_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
+            let get_votes =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
+            let get_votes_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+                .await;
+            assert_eq!(
+                get_votes_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
+
+            // Get total voters on proposal
+            let get_voters =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
+            let get_voters_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+                .await;
+            assert_eq!(
+                get_voters_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn instantiate_erc20(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc20 contract
+            let total_supply = 1_000;
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
+            let erc20_acc_id = client
+                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Transfer tokens to Bob
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer_to_bob = 200u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Bob
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+
+            // Get total supply
+            let total_supply_msg =
+                build_message::<Erc20Ref>(erc20_acc_id.clone()).call(|erc20| erc20.total_supply());
+            let total_supply_res = client
+                .call_dry_run(&ink_e2e::bob(), &total_supply_msg, 0, None)
+                .await;
+
+            assert_eq!(
+                total_supply,
+                total_supply_res.return_value(),
+                "total_supply"
+            );
+            assert_eq!(transfer_to_bob, balance_of_res.return_value(), "balance_of");
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn correct_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc20 contract
+            let total_supply = 1_000;
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
+            let erc20_acc_id = client
+                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            // Instantiate dao contract
+            let quorum = 10;
+            let dao_constructor =
+                DaoRef::new(erc20_acc_id, quorum, 20, Vec::new(), Config::default());
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Transfer tokens to Bob
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer_to_bob = 200u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Bob
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(transfer_to_bob, balance_of_res.return_value());
+
+            // Transfer tokens to Ferdie
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let transfer_to_ferdie = 100u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(ferdie_account.clone(), transfer_to_ferdie));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Ferdie
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(ferdie_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(transfer_to_ferdie, balance_of_res.return_value());
+
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await;
+            assert!(propose_result.is_ok());
+
+            // // Alice votes `yes`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::alice(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            // Ferdie votes `yes`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::ferdie(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            // Bob votes `no`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let _vote_result = client
+                .call(&ink_e2e::bob(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+
+            // Get total votes on proposal
+            let get_votes =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
+            let get_votes_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+                .await;
+            assert_eq!(
+                get_votes_result.return_value(),
+                Ok(ProposalVotes {
+                    total_yes: 800,
+                    total_no: 200,
+                })
+            );
 
-        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+            // Get total voters on proposal
+            let get_voters =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
+            let get_voters_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+                .await;
+            assert_eq!(get_voters_result.return_value(), Ok(3));
+            Ok(())
+        }
 
         #[ink_e2e::test]
-        async fn gets_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn incorrect_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc20 contract
+            let total_supply = 1_000;
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
+            let erc20_acc_id = client
+                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
             // Instantiate dao contract
-            let governance_token: AccountId = [0x08; 32].into();
             let quorum = 10;
-            let dao_constructor = DaoRef::new(governance_token, quorum);
+            let dao_constructor =
+                DaoRef::new(erc20_acc_id, quorum, 20, Vec::new(), Config::default());
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Get treasury balance
-            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_treasury_amount());
-            let get_treasury_amount_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+            // Transfer tokens to Bob
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer_to_bob = 200u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Bob
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
                 .await;
-            assert_eq!(get_treasury_amount_result.return_value(), 100);
-            // Test get_* without a proposal:
-            //
-            // Get end of proposal
-            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_proposal_end(1));
-            let get_end_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
+            assert_eq!(transfer_to_bob, balance_of_res.return_value());
+
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
                 .await;
-            assert_eq!(get_end_result.return_value(), Err(Error::ProposalNotFound));
-            // Get proposal
-            let get_proposal =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
-            let get_proposal_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+            assert!(propose_result.is_ok());
+
+            // Get balance of Ferdie (= 0)
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(ferdie_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
                 .await;
-            assert_eq!(
-                get_proposal_result.return_value(),
-                Err(Error::ProposalNotFound)
-            );
+            assert_eq!(balance_of_res.return_value(), 0);
+
+            // Ferdie votes `no` without balance (Error::InsufficientBalance)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let vote_result = client.call(&ink_e2e::ferdie(), vote_message, 0, None).await;
+            assert!(vote_result.is_err());
+
+            // Alice votes `yes`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::alice(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+
+            // Alice votes `yes` again (Error::AlreadyVoted)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let vote_result = client.call(&ink_e2e::alice(), vote_message, 0, None).await;
+            assert!(vote_result.is_err());
+
+            // Alice votes `no` again (Error::AlreadyVoted)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let vote_result = client.call(&ink_e2e::alice(), vote_message, 0, None).await;
+            assert!(vote_result.is_err());
+
+            // Bob votes `no` on non existing proposal (Error::ProposalNotFound)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(2, VoteType::No));
+            let vote_result = client.call(&ink_e2e::bob(), vote_message, 0, None).await;
+            assert!(vote_result.is_err());
+
             // Get total votes on proposal
             let get_votes =
                 ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
@@ -512,53 +3461,107 @@ mod dao {
                 .await;
             assert_eq!(
                 get_votes_result.return_value(),
-                Err(Error::ProposalNotFound)
-            );
-            // Test with a proposal:
-            //
-            // Propose a proposal
-            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
-            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
-            let _propose_result = client
-                .call(&ink_e2e::alice(), propose_message, 0, None)
-                .await
-                .expect("Proposal failed");
-            // Get end of proposal
-            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_proposal_end(1));
-            let get_end_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
-                .await;
-            assert!(get_end_result.exec_result.result.is_ok());
-            // Hacky way due to no timestamp() in e2e_tests yet
-            let start = get_end_result
-                .return_value()
-                .unwrap_or_else(|_| panic!("shouldn't panic"))
-                - (10 * DAYS);
-            // Get proposal
-            let get_proposal =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
-            let get_proposal_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
-                .await;
-            assert_eq!(
-                get_proposal_result.return_value(),
-                Ok(Proposal {
-                    to: ferdie_account,
-                    amount: 10,
-                    start,
-                    end: start + (10 * DAYS),
-                    executed: false,
+                Ok(ProposalVotes {
+                    total_yes: 800,
+                    total_no: 0,
                 })
             );
+
             // Get total voters on proposal
             let get_voters =
                 ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
             let get_voters_result = client
                 .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
                 .await;
-            assert_eq!(get_voters_result.return_value(), Ok(0));
+            assert_eq!(get_voters_result.return_value(), Ok(1));
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn incorrect_proposal_execution(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc20 contract
+            let total_supply = 1_000;
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
+            let erc20_acc_id = client
+                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            // Instantiate dao contract
+            let quorum = 10;
+            let dao_constructor =
+                DaoRef::new(erc20_acc_id, quorum, 20, Vec::new(), Config::default());
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Transfer tokens to Bob
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer_to_bob = 200u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Bob
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(transfer_to_bob, balance_of_res.return_value());
+
+            // Transfer tokens to Ferdie
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let transfer_to_ferdie = 100u128;
+            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.transfer(ferdie_account.clone(), transfer_to_ferdie));
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Get balance of Ferdie
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(ferdie_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(transfer_to_ferdie, balance_of_res.return_value());
+
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await;
+            assert!(propose_result.is_ok());
+
+            // // Alice votes `yes`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::alice(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            // Ferdie votes `no`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let _vote_result = client
+                .call(&ink_e2e::ferdie(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            // Bob votes `no`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let _vote_result = client
+                .call(&ink_e2e::bob(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
 
             // Get total votes on proposal
             let get_votes =
@@ -569,8 +3572,8 @@ mod dao {
             assert_eq!(
                 get_votes_result.return_value(),
                 Ok(ProposalVotes {
-                    total_yes: 0,
-                    total_no: 0,
+                    total_yes: 700,
+                    total_no: 300,
                 })
             );
 
@@ -580,62 +3583,144 @@ mod dao {
             let get_voters_result = client
                 .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
                 .await;
-            assert_eq!(get_voters_result.return_value(), Ok(0));
+            assert_eq!(get_voters_result.return_value(), Ok(3));
+
+            // Execute proposal (Error::ProposalStillActive)
+            let execute_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.execute(1));
+            let execute_result = client.call(&ink_e2e::bob(), execute_message, 0, None).await;
+            assert!(execute_result.is_err());
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn incorrect_proposals(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn fund(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc20 contract
+            let total_supply = 1_000;
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
+            let erc20_acc_id = client
+                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
             // Instantiate dao contract
-            let governance_token: AccountId = [0x08; 32].into();
             let quorum = 10;
-            let dao_constructor = DaoRef::new(governance_token, quorum);
+            let dao_constructor =
+                DaoRef::new(erc20_acc_id, quorum, 20, Vec::new(), Config::default());
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Get treasury balance
+            // Get treasury balance (100)
             let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.get_treasury_amount());
             let get_treasury_amount_result = client
                 .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
                 .await;
             assert_eq!(get_treasury_amount_result.return_value(), 100);
-            // Propose an incorrect proposals
-            //
-            // Invalid proposal amount (Error::InvalidProposalAmount)
-            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
-            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.propose(ferdie_account.clone(), 0, 10));
-            let propose_result = client
-                .call(&ink_e2e::alice(), propose_message, 0, None)
+            // Fund the dao contract (50)
+            let fund_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.fund());
+            let _fund_result = client
+                .call(&ink_e2e::bob(), fund_message, 50, None)
+                .await
+                .expect("fund failed");
+            // Get treasury balance (150)
+            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_treasury_amount());
+            let get_treasury_amount_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
                 .await;
-            assert!(propose_result.is_err());
-            // Invalid proposal duration (Error::InvalidProposalDuration)
+            assert_eq!(get_treasury_amount_result.return_value(), 150);
+            // Both contributions are recorded
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let get_contribution = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_contribution(bob_account));
+            let get_contribution_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_contribution, 0, None)
+                .await;
+            assert_eq!(get_contribution_result.return_value(), 50);
+            let get_total_inflow =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_total_inflow());
+            let get_total_inflow_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_total_inflow, 0, None)
+                .await;
+            assert_eq!(get_total_inflow_result.return_value(), 150);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn erc1155_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc1155 contract and create a badge for Alice
+            let erc1155_constructor = Erc1155Ref::new();
+            let erc1155_acc_id = client
+                .instantiate("erc_1155", &ink_e2e::alice(), erc1155_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let create = build_message::<Erc1155Ref>(erc1155_acc_id.clone())
+                .call(|erc1155| erc1155.create(10));
+            let token_id = client
+                .call(&ink_e2e::alice(), create, 0, None)
+                .await
+                .expect("create failed")
+                .return_value();
+            // Give Bob 3 badges
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer = build_message::<Erc1155Ref>(erc1155_acc_id.clone()).call(|erc1155| {
+                erc1155.safe_transfer_from(alice_account, bob_account, token_id, 3, Vec::new())
+            });
+            let _transfer_res = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            // Instantiate dao contract with voting power from the badge
+            let dao_constructor = DaoRef::new_erc1155(
+                erc1155_acc_id,
+                token_id,
+                10,
+                20,
+                Vec::new(),
+                Config::default(),
+            );
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
             let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.propose(ferdie_account.clone(), 10, 0));
-            let propose_result = client
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let _propose_result = client
                 .call(&ink_e2e::alice(), propose_message, 0, None)
-                .await;
-            assert!(propose_result.is_err());
+                .await
+                .expect("propose failed");
 
-            // Get checks
-            let get_end = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_proposal_end(1));
-            let get_end_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_end, 0, None)
-                .await;
-            assert_eq!(get_end_result.return_value(), Err(Error::ProposalNotFound));
-            let get_proposal =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
-            let get_proposal_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+            // Alice votes `yes` and Bob votes `no`
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::alice(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::No));
+            let _vote_result = client
+                .call(&ink_e2e::bob(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            // Charlie has no badge (Error::InsufficientBalance)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let vote_result = client
+                .call_dry_run(&ink_e2e::charlie(), &vote_message, 0, None)
                 .await;
-            assert_eq!(
-                get_proposal_result.return_value(),
-                Err(Error::ProposalNotFound)
-            );
+            assert_eq!(vote_result.return_value(), Err(Error::InsufficientBalance));
+
+            // The votes are the badge balances
             let get_votes =
                 ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
             let get_votes_result = client
@@ -643,261 +3728,252 @@ mod dao {
                 .await;
             assert_eq!(
                 get_votes_result.return_value(),
-                Err(Error::ProposalNotFound)
-            );
-
-            // Get total voters on proposal
-            let get_voters =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
-            let get_voters_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
-                .await;
-            assert_eq!(
-                get_voters_result.return_value(),
-                Err(Error::ProposalNotFound)
+                Ok(ProposalVotes {
+                    total_yes: 7,
+                    total_no: 3,
+                })
             );
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn instantiate_erc20(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Instantiate erc20 contract
-            let total_supply = 1_000;
-            let erc20_constructor = Erc20Ref::new(total_supply);
-            let erc20_acc_id = client
-                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+        async fn erc1155_escrow_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate erc1155 contract and create a badge for Alice
+            let erc1155_constructor = Erc1155Ref::new();
+            let erc1155_acc_id = client
+                .instantiate("erc_1155", &ink_e2e::alice(), erc1155_constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
-
-            // Transfer tokens to Bob
-            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let transfer_to_bob = 200u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
+            let create = build_message::<Erc1155Ref>(erc1155_acc_id.clone())
+                .call(|erc1155| erc1155.create(10));
+            let token_id = client
+                .call(&ink_e2e::alice(), create, 0, None)
                 .await
-                .expect("transfer failed");
+                .expect("create failed")
+                .return_value();
+            // Instantiate dao contract with escrow voting
+            let config = Config {
+                escrow_voting: true,
+                ..Default::default()
+            };
+            let dao_constructor =
+                DaoRef::new_erc1155(erc1155_acc_id, token_id, 10, 20, Vec::new(), config);
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+            // Propose a proposal
+            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
+            let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
+            let _propose_result = client
+                .call(&ink_e2e::alice(), propose_message, 0, None)
+                .await
+                .expect("propose failed");
 
-            // Get balance of Bob
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
+            // Vote without approving the dao (Error::TokenTransferFailed)
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let vote_result = client
+                .call_dry_run(&ink_e2e::alice(), &vote_message, 0, None)
+                .await;
+            assert_eq!(vote_result.return_value(), Err(Error::TokenTransferFailed));
+
+            // Approve the dao and vote, the badges of Alice are escrowed
+            let approve = build_message::<Erc1155Ref>(erc1155_acc_id.clone())
+                .call(|erc1155| erc1155.set_approval_for_all(dao_id.clone(), true));
+            let _approve_res = client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let _vote_result = client
+                .call(&ink_e2e::alice(), vote_message, 0, None)
+                .await
+                .expect("vote failed");
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let get_escrow = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_escrow(1, alice_account));
+            let get_escrow_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_escrow, 0, None)
+                .await;
+            assert_eq!(get_escrow_result.return_value(), 10);
+            let balance_of = build_message::<Erc1155Ref>(erc1155_acc_id.clone())
+                .call(|erc1155| erc1155.balance_of(dao_id, token_id));
             let balance_of_res = client
                 .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
                 .await;
+            assert_eq!(balance_of_res.return_value(), 10);
 
-            // Get total supply
-            let total_supply_msg =
-                build_message::<Erc20Ref>(erc20_acc_id.clone()).call(|erc20| erc20.total_supply());
-            let total_supply_res = client
-                .call_dry_run(&ink_e2e::bob(), &total_supply_msg, 0, None)
+            // The badges can't be passed on to vote again (Error::InsufficientBalance)
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer = build_message::<Erc1155Ref>(erc1155_acc_id.clone()).call(|erc1155| {
+                erc1155.safe_transfer_from(alice_account, bob_account, token_id, 10, Vec::new())
+            });
+            let transfer_result = client
+                .call_dry_run(&ink_e2e::alice(), &transfer, 0, None)
+                .await;
+            assert!(transfer_result.return_value().is_err());
+            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.vote(1, VoteType::Yes));
+            let vote_result = client
+                .call_dry_run(&ink_e2e::bob(), &vote_message, 0, None)
                 .await;
+            assert_eq!(vote_result.return_value(), Err(Error::InsufficientBalance));
 
+            // Withdraw while voting (Error::ProposalStillActive)
+            let withdraw_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.withdraw_vote_tokens(1));
+            let withdraw_result = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw_message, 0, None)
+                .await;
             assert_eq!(
-                total_supply,
-                total_supply_res.return_value(),
-                "total_supply"
+                withdraw_result.return_value(),
+                Err(Error::ProposalStillActive)
             );
-            assert_eq!(transfer_to_bob, balance_of_res.return_value(), "balance_of");
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn correct_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn escrow_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // Instantiate erc20 contract
             let total_supply = 1_000;
-            let erc20_constructor = Erc20Ref::new(total_supply);
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
             let erc20_acc_id = client
                 .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
-            // Instantiate dao contract
-            let quorum = 10;
-            let dao_constructor = DaoRef::new(erc20_acc_id, quorum);
+            // Instantiate dao contract with escrow voting
+            let config = Config {
+                escrow_voting: true,
+                ..Default::default()
+            };
+            let dao_constructor = DaoRef::new(erc20_acc_id, 10, 20, Vec::new(), config);
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Transfer tokens to Bob
-            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let transfer_to_bob = 200u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
-                .await
-                .expect("transfer failed");
-
-            // Get balance of Bob
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
-                .await;
-            assert_eq!(transfer_to_bob, balance_of_res.return_value());
-
-            // Transfer tokens to Ferdie
-            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
-            let transfer_to_ferdie = 100u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(ferdie_account.clone(), transfer_to_ferdie));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
-                .await
-                .expect("transfer failed");
-
-            // Get balance of Ferdie
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(ferdie_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
-                .await;
-            assert_eq!(transfer_to_ferdie, balance_of_res.return_value());
-
             // Propose a proposal
             let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
             let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
-            let propose_result = client
+            let _propose_result = client
                 .call(&ink_e2e::alice(), propose_message, 0, None)
-                .await;
-            assert!(propose_result.is_ok());
+                .await
+                .expect("propose failed");
 
-            // // Alice votes `yes`
+            // Vote without allowance (Error::TokenTransferFailed)
             let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.vote(1, VoteType::Yes));
-            let _vote_result = client
-                .call(&ink_e2e::alice(), vote_message, 0, None)
+            let vote_result = client
+                .call_dry_run(&ink_e2e::alice(), &vote_message, 0, None)
+                .await;
+            assert_eq!(vote_result.return_value(), Err(Error::TokenTransferFailed));
+
+            // Approve the dao and vote, the tokens of Alice are escrowed
+            let approve = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.approve(dao_id.clone(), total_supply));
+            let _approve_res = client
+                .call(&ink_e2e::alice(), approve, 0, None)
                 .await
-                .expect("vote failed");
-            // Ferdie votes `yes`
+                .expect("approve failed");
             let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.vote(1, VoteType::Yes));
             let _vote_result = client
-                .call(&ink_e2e::ferdie(), vote_message, 0, None)
-                .await
-                .expect("vote failed");
-            // Bob votes `no`
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::No));
-            let _vote_result = client
-                .call(&ink_e2e::bob(), vote_message, 0, None)
+                .call(&ink_e2e::alice(), vote_message, 0, None)
                 .await
                 .expect("vote failed");
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let get_escrow = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_escrow(1, alice_account));
+            let get_escrow_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_escrow, 0, None)
+                .await;
+            assert_eq!(get_escrow_result.return_value(), total_supply);
+            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.balance_of(dao_id));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(balance_of_res.return_value(), total_supply);
 
-            // Get total votes on proposal
-            let get_votes =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
-            let get_votes_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+            // Withdraw while voting (Error::ProposalStillActive)
+            let withdraw_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.withdraw_vote_tokens(1));
+            let withdraw_result = client
+                .call_dry_run(&ink_e2e::alice(), &withdraw_message, 0, None)
                 .await;
             assert_eq!(
-                get_votes_result.return_value(),
-                Ok(ProposalVotes {
-                    total_yes: 800,
-                    total_no: 200,
-                })
+                withdraw_result.return_value(),
+                Err(Error::ProposalStillActive)
             );
-
-            // Get total voters on proposal
-            let get_voters =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
-            let get_voters_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
-                .await;
-            assert_eq!(get_voters_result.return_value(), Ok(3));
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn incorrect_voting(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Instantiate erc20 contract
+        async fn psp22_governance_token(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate the PSP22 token
             let total_supply = 1_000;
-            let erc20_constructor = Erc20Ref::new(total_supply);
-            let erc20_acc_id = client
-                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
+            let psp22_constructor = Psp22TokenRef::new(total_supply);
+            let psp22_acc_id = client
+                .instantiate("psp22_token", &ink_e2e::alice(), psp22_constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
-            // Instantiate dao contract
-            let quorum = 10;
-            let dao_constructor = DaoRef::new(erc20_acc_id, quorum);
+            // Instantiate dao contract with the PSP22 interface and escrow voting
+            let config = Config {
+                token_interface: TokenInterface::Psp22,
+                escrow_voting: true,
+                ..Default::default()
+            };
+            let dao_constructor = DaoRef::new(psp22_acc_id, 1, 20, Vec::new(), config);
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Transfer tokens to Bob
-            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let transfer_to_bob = 200u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
+            let approve = build_message::<Psp22TokenRef>(psp22_acc_id.clone())
+                .call(|token| token.approve(dao_id.clone(), total_supply));
+            let _approve_res = client
+                .call(&ink_e2e::alice(), approve, 0, None)
                 .await
-                .expect("transfer failed");
+                .expect("approve failed");
 
-            // Get balance of Bob
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+            // Ragequit pulls the tokens with `PSP22::transfer_from` and burns them with
+            // `PSP22Burnable::burn`
+            let ragequit_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.ragequit(100));
+            let ragequit_result = client
+                .call(&ink_e2e::alice(), ragequit_message, 0, None)
+                .await
+                .expect("ragequit failed");
+            assert_eq!(ragequit_result.return_value(), Ok(()));
+            let total_supply_message = build_message::<Psp22TokenRef>(psp22_acc_id.clone())
+                .call(|token| token.total_supply());
+            let total_supply_res = client
+                .call_dry_run(&ink_e2e::alice(), &total_supply_message, 0, None)
                 .await;
-            assert_eq!(transfer_to_bob, balance_of_res.return_value());
+            assert_eq!(total_supply_res.return_value(), 900);
 
-            // Propose a proposal
+            // The vote is weighted by `PSP22::balance_of` and escrowed
             let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
             let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
-            let propose_result = client
+            let _propose_result = client
                 .call(&ink_e2e::alice(), propose_message, 0, None)
-                .await;
-            assert!(propose_result.is_ok());
-
-            // Get balance of Ferdie (= 0)
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(ferdie_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
-                .await;
-            assert_eq!(balance_of_res.return_value(), 0);
-
-            // Ferdie votes `no` without balance (Error::InsufficientBalance)
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::No));
-            let vote_result = client.call(&ink_e2e::ferdie(), vote_message, 0, None).await;
-            assert!(vote_result.is_err());
-
-            // Alice votes `yes`
+                .await
+                .expect("propose failed");
             let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.vote(1, VoteType::Yes));
             let _vote_result = client
                 .call(&ink_e2e::alice(), vote_message, 0, None)
                 .await
                 .expect("vote failed");
-
-            // Alice votes `yes` again (Error::AlreadyVoted)
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::Yes));
-            let vote_result = client.call(&ink_e2e::alice(), vote_message, 0, None).await;
-            assert!(vote_result.is_err());
-
-            // Alice votes `no` again (Error::AlreadyVoted)
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::No));
-            let vote_result = client.call(&ink_e2e::alice(), vote_message, 0, None).await;
-            assert!(vote_result.is_err());
-
-            // Bob votes `no` on non existing proposal (Error::ProposalNotFound)
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(2, VoteType::No));
-            let vote_result = client.call(&ink_e2e::bob(), vote_message, 0, None).await;
-            assert!(vote_result.is_err());
-
-            // Get total votes on proposal
             let get_votes =
                 ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
             let get_votes_result = client
@@ -906,26 +3982,24 @@ mod dao {
             assert_eq!(
                 get_votes_result.return_value(),
                 Ok(ProposalVotes {
-                    total_yes: 800,
+                    total_yes: 900,
                     total_no: 0,
                 })
             );
-
-            // Get total voters on proposal
-            let get_voters =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
-            let get_voters_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+            let balance_of = build_message::<Psp22TokenRef>(psp22_acc_id.clone())
+                .call(|token| token.balance_of(dao_id));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
                 .await;
-            assert_eq!(get_voters_result.return_value(), Ok(1));
+            assert_eq!(balance_of_res.return_value(), 900);
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn incorrect_proposal_execution(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn ragequit(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // Instantiate erc20 contract
             let total_supply = 1_000;
-            let erc20_constructor = Erc20Ref::new(total_supply);
+            let erc20_constructor = Erc20Ref::new(total_supply, None, None, 0, Hash::default());
             let erc20_acc_id = client
                 .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
                 .await
@@ -933,148 +4007,240 @@ mod dao {
                 .account_id;
             // Instantiate dao contract
             let quorum = 10;
-            let dao_constructor = DaoRef::new(erc20_acc_id, quorum);
+            let dao_constructor =
+                DaoRef::new(erc20_acc_id, quorum, 20, Vec::new(), Config::default());
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Transfer tokens to Bob
-            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let transfer_to_bob = 200u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
-                .await
-                .expect("transfer failed");
 
-            // Get balance of Bob
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+            // Ragequit without allowance (Error::TokenTransferFailed)
+            let ragequit_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.ragequit(500));
+            let ragequit_result = client
+                .call(&ink_e2e::alice(), ragequit_message, 0, None)
                 .await;
-            assert_eq!(transfer_to_bob, balance_of_res.return_value());
+            assert!(ragequit_result.is_err());
 
-            // Transfer tokens to Ferdie
-            let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
-            let transfer_to_ferdie = 100u128;
-            let transfer = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.transfer(ferdie_account.clone(), transfer_to_ferdie));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
+            // Alice allows the dao to take her tokens
+            let approve = build_message::<Erc20Ref>(erc20_acc_id.clone())
+                .call(|erc20| erc20.approve(dao_id.clone(), 500));
+            let _approve_res = client
+                .call(&ink_e2e::alice(), approve, 0, None)
                 .await
-                .expect("transfer failed");
+                .expect("approve failed");
 
-            // Get balance of Ferdie
-            let balance_of = build_message::<Erc20Ref>(erc20_acc_id.clone())
-                .call(|erc20| erc20.balance_of(ferdie_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+            // Alice ragequits with half of the total supply
+            let ragequit_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.ragequit(500));
+            let _ragequit_result = client
+                .call(&ink_e2e::alice(), ragequit_message, 0, None)
+                .await
+                .expect("ragequit failed");
+
+            // Get treasury balance (50)
+            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                .call(|dao| dao.get_treasury_amount());
+            let get_treasury_amount_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
                 .await;
-            assert_eq!(transfer_to_ferdie, balance_of_res.return_value());
+            assert_eq!(get_treasury_amount_result.return_value(), 50);
 
-            // Propose a proposal
+            // The governance tokens have been burned
+            let total_supply_msg =
+                build_message::<Erc20Ref>(erc20_acc_id.clone()).call(|erc20| erc20.total_supply());
+            let total_supply_res = client
+                .call_dry_run(&ink_e2e::bob(), &total_supply_msg, 0, None)
+                .await;
+            assert_eq!(total_supply_res.return_value(), 500);
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn reentrant_calls_fail(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate the malicious governance token
+            let attacker_constructor = ReentrancyAttackerRef::new(1_000);
+            let attacker_acc_id = client
+                .instantiate(
+                    "reentrancy_attacker",
+                    &ink_e2e::alice(),
+                    attacker_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            // Instantiate dao contract, the governance token is also a guardian
+            let quorum = 1;
+            let dao_constructor = DaoRef::new(
+                attacker_acc_id,
+                quorum,
+                20,
+                vec![attacker_acc_id],
+                Config::default(),
+            );
+            let dao_id = client
+                .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
+                .await
+                .expect("dao contract instantiation failed")
+                .account_id;
+
+            // Propose a proposal of one minute and accept it
             let ferdie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Ferdie);
             let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.propose(ferdie_account.clone(), 10, 10));
-            let propose_result = client
+                .call(|dao| dao.propose(ferdie_account.clone(), 10, 1));
+            let _propose_result = client
                 .call(&ink_e2e::alice(), propose_message, 0, None)
-                .await;
-            assert!(propose_result.is_ok());
-
-            // // Alice votes `yes`
+                .await
+                .expect("propose failed");
             let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
                 .call(|dao| dao.vote(1, VoteType::Yes));
             let _vote_result = client
                 .call(&ink_e2e::alice(), vote_message, 0, None)
                 .await
                 .expect("vote failed");
-            // Ferdie votes `no`
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::No));
-            let _vote_result = client
-                .call(&ink_e2e::ferdie(), vote_message, 0, None)
-                .await
-                .expect("vote failed");
-            // Bob votes `no`
-            let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.vote(1, VoteType::No));
-            let _vote_result = client
-                .call(&ink_e2e::bob(), vote_message, 0, None)
-                .await
-                .expect("vote failed");
+            // Let the voting period pass, the proposal can then be executed and vetoed
+            std::thread::sleep(std::time::Duration::from_millis(DAYS + 6_000));
 
-            // Get total votes on proposal
-            let get_votes =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_votes(1));
-            let get_votes_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+            // While the DAO runs `fn ragequit`, which calls `transfer_from` on the
+            // governance token, the token calls back into the DAO. The DAO doesn't allow
+            // reentry on its calls, so the runtime rejects the call back.
+            for reentry in [
+                Reentry::Execute(1),
+                Reentry::Ragequit(100),
+                Reentry::Veto(1),
+                Reentry::Propose(10),
+            ] {
+                let set_target = build_message::<ReentrancyAttackerRef>(attacker_acc_id.clone())
+                    .call(|attacker| attacker.set_target(dao_id.clone(), reentry));
+                let _set_target_res = client
+                    .call(&ink_e2e::alice(), set_target, 0, None)
+                    .await
+                    .expect("set target failed");
+                let ragequit_message =
+                    ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.ragequit(100));
+                let _ragequit_result = client
+                    .call(&ink_e2e::alice(), ragequit_message, 0, None)
+                    .await
+                    .expect("ragequit failed");
+                let last_result = build_message::<ReentrancyAttackerRef>(attacker_acc_id.clone())
+                    .call(|attacker| attacker.last_result());
+                let last_result_res = client
+                    .call_dry_run(&ink_e2e::alice(), &last_result, 0, None)
+                    .await;
+                assert_eq!(last_result_res.return_value(), None);
+            }
+
+            // The token did call back every time
+            let call_backs = build_message::<ReentrancyAttackerRef>(attacker_acc_id.clone())
+                .call(|attacker| attacker.call_backs());
+            let call_backs_res = client
+                .call_dry_run(&ink_e2e::alice(), &call_backs, 0, None)
                 .await;
-            assert_eq!(
-                get_votes_result.return_value(),
-                Ok(ProposalVotes {
-                    total_yes: 700,
-                    total_no: 300,
-                })
-            );
+            assert_eq!(call_backs_res.return_value(), 4);
 
-            // Get total voters on proposal
-            let get_voters =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_voters(1));
-            let get_voters_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_voters, 0, None)
+            // Neither the execution, the veto nor the proposal went through
+            let get_vetoes =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_vetoes(1));
+            let get_vetoes_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_vetoes, 0, None)
                 .await;
-            assert_eq!(get_voters_result.return_value(), Ok(3));
+            assert_eq!(get_vetoes_result.return_value(), Ok(0));
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(1));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert!(!get_proposal_result.return_value().unwrap().executed);
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(2));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert_eq!(
+                get_proposal_result.return_value(),
+                Err(Error::ProposalNotFound)
+            );
 
-            // Execute proposal (Error::ProposalStillActive)
+            // Outside of `fn ragequit` the proposal can be executed
             let execute_message =
                 ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.execute(1));
-            let execute_result = client.call(&ink_e2e::bob(), execute_message, 0, None).await;
-            assert!(execute_result.is_err());
+            let execute_result = client
+                .call_dry_run(&ink_e2e::alice(), &execute_message, 0, None)
+                .await;
+            assert_eq!(execute_result.return_value(), Ok(()));
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn fund(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Instantiate erc20 contract
-            let total_supply = 1_000;
-            let erc20_constructor = Erc20Ref::new(total_supply);
-            let erc20_acc_id = client
-                .instantiate("erc20", &ink_e2e::alice(), erc20_constructor, 0, None)
-                .await
-                .expect("instantiate failed")
-                .account_id;
-            // Instantiate dao contract
-            let quorum = 10;
-            let dao_constructor = DaoRef::new(erc20_acc_id, quorum);
+        async fn set_code_hash(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Instantiate dao contract with Alice as its only member
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let dao_constructor =
+                DaoRef::new_membership(vec![alice_account], 50, 20, Vec::new(), Config::default());
             let dao_id = client
                 .instantiate("dao", &ink_e2e::ferdie(), dao_constructor, 100, None)
                 .await
                 .expect("dao contract instantiation failed")
                 .account_id;
-            // Get treasury balance (100)
-            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_treasury_amount());
-            let get_treasury_amount_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+            // The code to upgrade to
+            let code_hash = client
+                .upload("dao", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            // Propose code that has not been uploaded and the uploaded code, and accept both
+            for code_hash in [Hash::from([0x01; 32]), code_hash] {
+                let propose_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                    .call(|dao| dao.propose_set_code_hash(code_hash, 1));
+                let _propose_result = client
+                    .call(&ink_e2e::alice(), propose_message, 0, None)
+                    .await
+                    .expect("propose failed");
+            }
+            for proposal_id in [1, 2] {
+                let vote_message = ink_e2e::build_message::<DaoRef>(dao_id.clone())
+                    .call(|dao| dao.vote(proposal_id, VoteType::Yes));
+                let _vote_result = client
+                    .call(&ink_e2e::alice(), vote_message, 0, None)
+                    .await
+                    .expect("vote failed");
+            }
+            // Let the voting period pass
+            std::thread::sleep(std::time::Duration::from_millis(DAYS + 6_000));
+
+            // Execute with code that has not been uploaded (Error::CodeNotFound)
+            let execute_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.execute(1));
+            let execute_result = client
+                .call_dry_run(&ink_e2e::alice(), &execute_message, 0, None)
                 .await;
-            assert_eq!(get_treasury_amount_result.return_value(), 100);
-            // Fund the dao contract (50)
-            let fund_message =
-                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.fund());
-            let _fund_result = client
-                .call(&ink_e2e::bob(), fund_message, 50, None)
+            assert_eq!(execute_result.return_value(), Err(Error::CodeNotFound));
+
+            // Execute with the uploaded code
+            let execute_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.execute(2));
+            let _execute_result = client
+                .call(&ink_e2e::alice(), execute_message, 0, None)
                 .await
-                .expect("fund failed");
-            // Get treasury balance (150)
-            let get_treasury_amount = ink_e2e::build_message::<DaoRef>(dao_id.clone())
-                .call(|dao| dao.get_treasury_amount());
-            let get_treasury_amount_result = client
-                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+                .expect("execute failed");
+
+            // The new code is at the current storage version
+            let migrate_message =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.migrate());
+            let migrate_result = client
+                .call_dry_run(&ink_e2e::bob(), &migrate_message, 0, None)
                 .await;
-            assert_eq!(get_treasury_amount_result.return_value(), 150);
+            assert_eq!(migrate_result.return_value(), Err(Error::AlreadyMigrated));
+            let get_proposal =
+                ink_e2e::build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_proposal(2));
+            let get_proposal_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_proposal, 0, None)
+                .await;
+            assert!(get_proposal_result.return_value().unwrap().executed);
             Ok(())
         }
 
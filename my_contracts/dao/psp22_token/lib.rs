@@ -0,0 +1,171 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::psp22_token::{Psp22Token, Psp22TokenRef};
+
+// A minimal PSP22 token, used to test the DAO with `TokenInterface::Psp22`. It only has
+// the messages the DAO calls: `PSP22` and `PSP22Burnable`.
+#[ink::contract]
+mod psp22_token {
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::Mapping;
+    use token_traits::{PSP22Burnable, Psp22Error, PSP22};
+
+    type Result<T> = core::result::Result<T, Psp22Error>;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Psp22Token {
+        // Total token supply.
+        total_supply: Balance,
+        // Mapping from owner to number of owned token.
+        balances: Mapping<AccountId, Balance>,
+        // Mapping of the token amount which an account is allowed to withdraw from
+        // another account.
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    impl Psp22Token {
+        // Creates a new token with `total_supply` owned by the caller.
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut balances = Mapping::default();
+            balances.insert(Self::env().caller(), &total_supply);
+            Self {
+                total_supply,
+                balances,
+                allowances: Mapping::default(),
+            }
+        }
+
+        #[inline]
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(Psp22Error::InsufficientBalance);
+            }
+            self.balances.insert(from, &(from_balance - value));
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, &(to_balance + value));
+            Ok(())
+        }
+    }
+
+    impl PSP22 for Psp22Token {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<()> {
+            self.transfer_from_to(self.env().caller(), to, value)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value {
+                return Err(Psp22Error::InsufficientAllowance);
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.allowances.insert((from, caller), &(allowance - value));
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            self.allowances
+                .insert((self.env().caller(), spender), &value);
+            Ok(())
+        }
+    }
+
+    impl PSP22Burnable for Psp22Token {
+        // Accounts can only burn their own tokens.
+        #[ink(message)]
+        fn burn(&mut self, account: AccountId, value: Balance) -> Result<()> {
+            if account != self.env().caller() {
+                return Err(Psp22Error::Custom(String::from("can only burn own tokens")));
+            }
+            let balance = self.balance_of(account);
+            if balance < value {
+                return Err(Psp22Error::InsufficientBalance);
+            }
+            self.balances.insert(account, &(balance - value));
+            self.total_supply -= value;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // The selector the DAO calls a message of a token trait with.
+        macro_rules! selector {
+            ($trait:ident, $message:literal) => {
+                <<ink::reflect::TraitDefinitionRegistry<ink::env::DefaultEnvironment>
+                    as $trait>::__ink_TraitInfo
+                    as ink::reflect::TraitMessageInfo<{ ink::selector_id!($message) }>>::SELECTOR
+            };
+        }
+
+        #[ink::test]
+        fn selectors_match_the_standard() {
+            assert_eq!(selector!(PSP22, "total_supply"), [0x16, 0x2d, 0xf8, 0xc2]);
+            assert_eq!(selector!(PSP22, "balance_of"), [0x65, 0x68, 0x38, 0x2f]);
+            assert_eq!(selector!(PSP22, "allowance"), [0x4d, 0x47, 0xd9, 0x21]);
+            assert_eq!(selector!(PSP22, "transfer"), [0xdb, 0x20, 0xf9, 0xf5]);
+            assert_eq!(selector!(PSP22, "transfer_from"), [0x54, 0xb3, 0xc7, 0x6e]);
+            assert_eq!(selector!(PSP22, "approve"), [0xb2, 0x0f, 0x1b, 0xbd]);
+            assert_eq!(selector!(PSP22Burnable, "burn"), [0x7a, 0x9d, 0xa5, 0x10]);
+        }
+
+        #[ink::test]
+        fn transfer_from_and_burn_work() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = Psp22Token::new(100);
+            assert_eq!(token.approve(accounts.bob, 30), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.bob, 40, Vec::new()),
+                Err(Psp22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.bob, 30, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            assert!(token.burn(accounts.alice, 10).is_err());
+            assert_eq!(token.burn(accounts.bob, 10), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 20);
+            assert_eq!(token.total_supply(), 90);
+        }
+
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::reentrancy_attacker::{ReentrancyAttacker, ReentrancyAttackerRef, Reentry};
+
+// A malicious governance token, used to test the DAO against reentrancy. Every account
+// holds the same balance and when the DAO calls `transfer_from` (e.g. in `fn ragequit`)
+// it calls back into the DAO before returning.
+#[ink::contract]
+mod reentrancy_attacker {
+    use ink::env::{
+        call::{build_call, ExecutionInput, Selector},
+        DefaultEnvironment,
+    };
+    use token_traits::{Erc20, Erc20Burnable, Result};
+
+    // The message of the DAO to call back into.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub enum Reentry {
+        // `fn execute` of a proposal.
+        Execute(u64),
+        // `fn ragequit` with an amount.
+        Ragequit(Balance),
+        // `fn veto` of a proposal, the attacker has to be a guardian.
+        Veto(u64),
+        // `fn propose` of a transfer of an amount to the attacker.
+        Propose(Balance),
+    }
+
+    #[ink(storage)]
+    pub struct ReentrancyAttacker {
+        // The balance of every account.
+        balance: Balance,
+        // The DAO and the message to call back into.
+        target: Option<(AccountId, Reentry)>,
+        // What the DAO returned to the last call back, its errors are encoded as their
+        // index.
+        last_result: Option<core::result::Result<(), u8>>,
+        // The number of calls back into the DAO that have been made.
+        call_backs: u32,
+    }
+
+    impl ReentrancyAttacker {
+        #[ink(constructor)]
+        pub fn new(balance: Balance) -> Self {
+            Self {
+                balance,
+                target: None,
+                last_result: None,
+                call_backs: 0,
+            }
+        }
+
+        // Set the DAO and the message to call when the DAO calls this contract.
+        #[ink(message)]
+        pub fn set_target(&mut self, dao: AccountId, reentry: Reentry) {
+            self.target = Some((dao, reentry));
+        }
+
+        // What the DAO returned to the last call back, `None` when the call itself
+        // failed (e.g. the runtime denied the reentry) or no call back has been made.
+        #[ink(message)]
+        pub fn last_result(&self) -> Option<core::result::Result<(), u8>> {
+            self.last_result
+        }
+
+        // The number of calls back into the DAO that have been made.
+        #[ink(message)]
+        pub fn call_backs(&self) -> u32 {
+            self.call_backs
+        }
+
+        #[inline]
+        fn reenter(&mut self) {
+            let (dao, reentry) = match self.target {
+                Some(target) => target,
+                None => return,
+            };
+            self.call_backs += 1;
+            self.last_result = match reentry {
+                Reentry::Execute(proposal_id) => Self::call_dao(
+                    dao,
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("execute")))
+                        .push_arg(proposal_id),
+                ),
+                Reentry::Ragequit(amount) => Self::call_dao(
+                    dao,
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("ragequit")))
+                        .push_arg(amount),
+                ),
+                Reentry::Veto(proposal_id) => Self::call_dao(
+                    dao,
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("veto")))
+                        .push_arg(proposal_id),
+                ),
+                Reentry::Propose(amount) => Self::call_dao(
+                    dao,
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("propose")))
+                        .push_arg(self.env().account_id())
+                        .push_arg(amount)
+                        .push_arg(1u64),
+                ),
+            };
+        }
+
+        #[inline]
+        fn call_dao<Args: scale::Encode>(
+            dao: AccountId,
+            input: ExecutionInput<Args>,
+        ) -> Option<core::result::Result<(), u8>> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(dao)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(input)
+                .returns::<core::result::Result<(), u8>>()
+                .try_invoke();
+            match result {
+                Ok(Ok(result)) => Some(result),
+                _ => None,
+            }
+        }
+    }
+
+    impl Erc20 for ReentrancyAttacker {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.balance
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, _owner: AccountId) -> Balance {
+            self.balance
+        }
+
+        #[ink(message)]
+        fn allowance(&self, _owner: AccountId, _spender: AccountId) -> Balance {
+            self.balance
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, _to: AccountId, _value: Balance) -> Result<()> {
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, _spender: AccountId, _value: Balance) -> Result<()> {
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            _from: AccountId,
+            _to: AccountId,
+            _value: Balance,
+        ) -> Result<()> {
+            self.reenter();
+            Ok(())
+        }
+    }
+
+    impl Erc20Burnable for ReentrancyAttacker {
+        #[ink(message)]
+        fn burn(&mut self, _from: AccountId, _value: Balance) -> Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -1,11 +1,17 @@
 #[cfg(test)]
 mod test {
-    use crate::dao::{Dao, Error, Proposal, ProposalVotes, VoteType};
-    use ink::primitives::AccountId;
-    // type Event = <Dao as ::ink::reflect::ContractEventBase>::Type;
+    use crate::dao::{
+        ApprovalTier, CommitteeSpec, Config, Dao, Error, ExecutionReport, Fund, Majority, Proposal,
+        ProposalAction, ProposalKind, ProposalState, ProposalVetoed, ProposalVotes,
+        RecurringSchedule, SpendingLimit, VoteReceipt, VoteType, VotingSource,
+    };
+    use ink::primitives::{AccountId, Hash, KeyComposer};
+    use ink::storage::traits::Storable;
+    type Event = <Dao as ::ink::reflect::ContractEventBase>::Type;
     type Balance = u128;
     type Timestamp = u64;
     const DAYS: u64 = 60 * 1_000;
+    const VETO_THRESHOLD: u8 = 20;
 
     #[ink::test]
     fn create_dao() {
@@ -31,7 +37,14 @@ mod test {
         assert!(propose_result.is_ok());
         // verify with `get_proposal`
         if let Ok(proposal) = dao.get_proposal(1) {
-            assert_proposal(proposal, 50, accounts.django, 10, 10);
+            assert_proposal(
+                proposal,
+                50,
+                accounts.django,
+                10,
+                10,
+                ProposalKind::Standard,
+            );
         } else {
             panic!("Proposal did not exist while it should");
         }
@@ -141,6 +154,13 @@ mod test {
         };
         dao.proposal_votes.insert(1, &proposal_votes);
         dao.votes.insert((1, accounts.bob), &());
+        dao.vote_receipts.insert(
+            (1, accounts.bob),
+            &VoteReceipt {
+                weight: 100,
+                vote_type: VoteType::Yes,
+            },
+        );
         dao.total_voters.insert(1, &1);
         // Bob votes again
         set_sender(accounts.bob);
@@ -158,13 +178,1540 @@ mod test {
         assert_eq!(end, Ok(10 * DAYS));
     }
 
+    #[ink::test]
+    fn more_than_255_voters() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        // 255 voters voted before (hacky way)
+        dao.total_voters.insert(1, &255);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.get_voters(1), Ok(256));
+        dao.total_voters.insert(1, &u32::MAX);
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(1, VoteType::Yes), Err(Error::ArithmeticOverflow));
+    }
+
+    #[ink::test]
+    fn correct_optimistic_proposal() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let quorum = 10;
+        let mut dao = create_contract(governance_token, quorum, 1000);
+        let accounts = default_accounts();
+        // Optimistic proposal
+        set_block_timestamp(50);
+        let propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        assert!(propose_result.is_ok());
+        if let Ok(proposal) = dao.get_proposal(1) {
+            assert_proposal(
+                proposal,
+                50,
+                accounts.django,
+                10,
+                10,
+                ProposalKind::Optimistic,
+            );
+        } else {
+            panic!("Proposal did not exist while it should");
+        }
+        // Incorrect optimistic proposals
+        let propose_result = dao.propose_optimistic(accounts.django, 0, 10);
+        assert_eq!(propose_result, Err(Error::InvalidProposalAmount));
+        let propose_result = dao.propose_optimistic(accounts.django, 10, 0);
+        assert_eq!(propose_result, Err(Error::InvalidProposalDuration));
+        assert_eq!(dao.get_proposal(2), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn execute_optimistic_proposal_without_votes() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let quorum = 10;
+        let mut dao = create_contract(governance_token, quorum, 1000);
+        let accounts = default_accounts();
+        let django_balance = get_balance(accounts.django);
+        // Standard and optimistic proposal without any votes
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        let _propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        assert_eq!(dao.execute(2), Err(Error::ProposalStillActive));
+        set_block_timestamp(10 * DAYS);
+        // The standard proposal needs the quorum to be met
+        assert_eq!(dao.execute(1), Err(Error::QuorumNotMet));
+        // The optimistic proposal passes automatically
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(dao.execute(2), Err(Error::ProposalExecuted));
+        assert_eq!(get_balance(accounts.django), django_balance + 10);
+        assert_eq!(dao.get_treasury_amount(), 990);
+        let proposal = dao
+            .get_proposal(2)
+            .unwrap_or_else(|_| panic!("proposal should exist"));
+        assert!(proposal.executed);
+    }
+
+    #[ink::test]
+    fn create_dao_with_guardians() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let accounts = default_accounts();
+        let dao = create_contract_with_guardians(
+            governance_token,
+            10,
+            1000,
+            vec![accounts.bob, accounts.charlie, accounts.bob],
+        );
+        assert_eq!(dao.get_guardians(), vec![accounts.bob, accounts.charlie]);
+    }
+
+    #[ink::test]
+    fn veto_succeeded_proposal() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let accounts = default_accounts();
+        let mut dao = create_contract_with_guardians(
+            governance_token,
+            10,
+            1000,
+            vec![accounts.bob, accounts.charlie, accounts.eve],
+        );
+        let _propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        // Only guardians can veto
+        set_sender(accounts.django);
+        assert_eq!(dao.veto(1), Err(Error::NotGuardian));
+        // The proposal has not succeeded yet
+        set_sender(accounts.bob);
+        assert_eq!(dao.veto(1), Err(Error::ProposalStillActive));
+        assert_eq!(dao.veto(2), Err(Error::ProposalNotFound));
+        set_block_timestamp(10 * DAYS);
+        // Bob vetoes, which is not a majority yet
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.veto(1), Err(Error::AlreadyVetoed));
+        assert_eq!(dao.get_vetoes(1), Ok(1));
+        assert!(!dao.get_proposal(1).map(|p| p.vetoed).unwrap_or(true));
+        // Charlie vetoes, which makes a majority
+        set_sender(accounts.charlie);
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.get_vetoes(1), Ok(2));
+        assert!(dao.get_proposal(1).map(|p| p.vetoed).unwrap_or(false));
+        let emitted_events = get_events();
+        assert_proposal_vetoed_event(&emitted_events[emitted_events.len() - 1], 1, 2);
+        // Eve can not veto anymore and nobody can execute
+        set_sender(accounts.eve);
+        assert_eq!(dao.veto(1), Err(Error::ProposalVetoed));
+        assert_eq!(dao.execute(1), Err(Error::ProposalVetoed));
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn veto_refused_proposal() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let accounts = default_accounts();
+        let mut dao =
+            create_contract_with_guardians(governance_token, 10, 1000, vec![accounts.bob]);
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        set_block_timestamp(10 * DAYS);
+        set_sender(accounts.bob);
+        assert_eq!(dao.veto(1), Err(Error::QuorumNotMet));
+        assert_eq!(dao.get_vetoes(1), Ok(0));
+    }
+
+    #[ink::test]
+    fn guardian_management() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let quorum = 10;
+        let accounts = default_accounts();
+        let mut dao =
+            create_contract_with_guardians(governance_token, quorum, 1000, vec![accounts.bob]);
+        // Incorrect guardian proposals
+        assert_eq!(
+            dao.propose_add_guardian(accounts.bob, 10),
+            Err(Error::AlreadyGuardian)
+        );
+        assert_eq!(
+            dao.propose_remove_guardian(accounts.charlie, 10),
+            Err(Error::GuardianNotFound)
+        );
+        assert_eq!(
+            dao.propose_add_guardian(accounts.charlie, 0),
+            Err(Error::InvalidProposalDuration)
+        );
+        // Add Charlie and remove Bob
+        assert_eq!(dao.propose_add_guardian(accounts.charlie, 10), Ok(()));
+        assert_eq!(dao.propose_remove_guardian(accounts.bob, 10), Ok(()));
+        let proposal = dao
+            .get_proposal(1)
+            .unwrap_or_else(|_| panic!("proposal should exist"));
+        assert_eq!(proposal.action, ProposalAction::AddGuardian);
+        assert_eq!(proposal.to, accounts.charlie);
+        assert_eq!(proposal.amount, 0);
+        accept_proposal(&mut dao, 1, quorum);
+        accept_proposal(&mut dao, 2, quorum);
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.get_guardians(), vec![accounts.bob, accounts.charlie]);
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(dao.get_guardians(), vec![accounts.charlie]);
+        // Guardian proposals don't touch the treasury
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn removed_guardian_vetoes_dont_count() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let quorum = 10;
+        let accounts = default_accounts();
+        let mut dao = create_contract_with_guardians(
+            governance_token,
+            quorum,
+            1000,
+            vec![accounts.bob, accounts.charlie, accounts.eve],
+        );
+        let _propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        assert_eq!(dao.propose_remove_guardian(accounts.bob, 20), Ok(()));
+        accept_proposal(&mut dao, 2, quorum);
+        set_block_timestamp(10 * DAYS);
+        set_sender(accounts.bob);
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.get_vetoes(1), Ok(1));
+        set_block_timestamp(20 * DAYS);
+        assert_eq!(dao.execute(2), Ok(()));
+        // Bob has been removed, his veto no longer counts
+        assert_eq!(dao.get_vetoes(1), Ok(0));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.get_vetoes(1), Ok(1));
+        assert!(!dao.get_proposal(1).map(|p| p.vetoed).unwrap_or(true));
+        // Two of the two current guardians make a majority
+        set_sender(accounts.eve);
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.get_vetoes(1), Ok(2));
+        assert_eq!(dao.execute(1), Err(Error::ProposalVetoed));
+    }
+
+    #[ink::test]
+    fn execution_timelock() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let accounts = default_accounts();
+        set_sender(accounts.alice);
+        set_balance(contract_id(), 1000);
+        let config = Config {
+            timelock: 2,
+            ..Default::default()
+        };
+        let mut dao = Dao::new(
+            governance_token,
+            10,
+            VETO_THRESHOLD,
+            vec![accounts.bob, accounts.charlie],
+            config,
+        );
+        let _propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Succeeded));
+        assert_eq!(dao.execute(1), Err(Error::ProposalTimelocked));
+        // Guardians can veto during the timelock
+        set_sender(accounts.bob);
+        assert_eq!(dao.veto(1), Ok(()));
+        set_block_timestamp(12 * DAYS - 1);
+        assert_eq!(dao.execute(1), Err(Error::ProposalTimelocked));
+        set_block_timestamp(12 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.get_treasury_amount(), 990);
+    }
+
+    #[ink::test]
+    fn ragequit_zero_amount() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let mut dao = create_contract(governance_token, 10, 1000);
+        assert_eq!(dao.ragequit(0), Err(Error::InvalidRagequitAmount));
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn create_membership_dao() {
+        let accounts = default_accounts();
+        let dao = create_membership_contract(
+            vec![accounts.alice, accounts.bob, accounts.alice],
+            50,
+            1000,
+        );
+        assert_eq!(dao.get_treasury_amount(), 1000);
+        assert_eq!(dao.get_total_members(), 2);
+        assert!(dao.is_member(accounts.alice));
+        assert!(dao.is_member(accounts.bob));
+        assert!(!dao.is_member(accounts.charlie));
+    }
+
+    #[ink::test]
+    fn membership_voting() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(
+            vec![
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ],
+            50,
+            1000,
+        );
+        let django_balance = get_balance(accounts.django);
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        let _propose_result = dao.propose(accounts.django, 20, 10);
+        // Only members can vote, once
+        set_sender(accounts.eve);
+        assert_eq!(dao.vote(1, VoteType::Yes), Err(Error::NotMember));
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.vote(1, VoteType::No), Err(Error::AlreadyVoted));
+        // Three out of four members vote on the second proposal
+        assert_eq!(dao.vote(2, VoteType::Yes), Ok(()));
+        set_sender(accounts.alice);
+        assert_eq!(dao.vote(2, VoteType::Yes), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(2, VoteType::No), Ok(()));
+        assert_eq!(
+            dao.get_votes(2),
+            Ok(ProposalVotes {
+                total_yes: 2,
+                total_no: 1,
+            })
+        );
+        assert_eq!(dao.get_voters(2), Ok(3));
+        set_block_timestamp(10 * DAYS);
+        // One out of four members is less than the quorum of 50%
+        assert_eq!(dao.execute(1), Err(Error::QuorumNotMet));
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(get_balance(accounts.django), django_balance + 20);
+    }
+
+    #[ink::test]
+    fn membership_veto_threshold() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(
+            vec![
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+            ],
+            50,
+            1000,
+        );
+        let _propose_result = dao.propose_optimistic(accounts.django, 10, 10);
+        // One out of four members reaches the veto threshold of 20%
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(1, VoteType::No), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Err(Error::VetoThresholdReached));
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn member_management() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.alice, accounts.bob], 50, 1000);
+        // Incorrect member proposals
+        assert_eq!(
+            dao.propose_add_member(accounts.bob, 10),
+            Err(Error::AlreadyMember)
+        );
+        assert_eq!(
+            dao.propose_remove_member(accounts.charlie, 10),
+            Err(Error::MemberNotFound)
+        );
+        // Add Charlie and remove Bob
+        assert_eq!(dao.propose_add_member(accounts.charlie, 10), Ok(()));
+        assert_eq!(dao.propose_remove_member(accounts.bob, 10), Ok(()));
+        for proposal_id in [1, 2] {
+            set_sender(accounts.alice);
+            assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+        }
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert!(dao.is_member(accounts.charlie));
+        assert_eq!(dao.get_total_members(), 3);
+        assert_eq!(dao.execute(2), Ok(()));
+        assert!(!dao.is_member(accounts.bob));
+        assert_eq!(dao.get_total_members(), 2);
+        // Bob can't vote anymore
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(3, VoteType::Yes), Err(Error::NotMember));
+    }
+
+    #[ink::test]
+    fn voting_source_mismatch() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let accounts = default_accounts();
+        let mut dao = create_contract(governance_token, 10, 1000);
+        assert_eq!(
+            dao.propose_add_member(accounts.bob, 10),
+            Err(Error::NoMembership)
+        );
+        assert_eq!(
+            dao.propose_remove_member(accounts.bob, 10),
+            Err(Error::NoMembership)
+        );
+        let mut dao = create_membership_contract(vec![accounts.alice], 50, 1000);
+        assert_eq!(dao.ragequit(10), Err(Error::NoGovernanceToken));
+        // Voting power from an ERC-1155 token id
+        let erc1155: AccountId = [0x09; 32].into();
+        let mut dao = Dao::new_erc1155(
+            erc1155,
+            1,
+            10,
+            VETO_THRESHOLD,
+            Vec::new(),
+            Config::default(),
+        );
+        assert_eq!(
+            dao.state.voting_source,
+            VotingSource::Erc1155Token(erc1155, 1)
+        );
+        assert_eq!(dao.ragequit(10), Err(Error::NoGovernanceToken));
+        assert_eq!(
+            dao.propose_add_member(accounts.bob, 10),
+            Err(Error::NoMembership)
+        );
+    }
+
+    #[ink::test]
+    fn erc1155_escrow_voting() {
+        let accounts = default_accounts();
+        let config = Config {
+            escrow_voting: true,
+            ..Default::default()
+        };
+        let erc1155: AccountId = [0x09; 32].into();
+        let mut dao = Dao::new_erc1155(erc1155, 1, 10, VETO_THRESHOLD, Vec::new(), config);
+        assert!(dao.state.config.escrow_voting);
+        assert_eq!(dao.propose(accounts.eve, 10, 1), Ok(()));
+        // Badges escrowed for a vote stay with the DAO until the proposal ends (hacky way)
+        dao.escrows.insert((1, accounts.bob), &3);
+        assert_eq!(dao.get_escrow(1, accounts.bob), 3);
+        set_sender(accounts.bob);
+        assert_eq!(dao.withdraw_vote_tokens(1), Err(Error::ProposalStillActive));
+        let proposal = dao.get_proposal(1).unwrap();
+        set_block_timestamp(proposal.end);
+        set_sender(accounts.charlie);
+        assert_eq!(dao.withdraw_vote_tokens(1), Err(Error::NoEscrowedTokens));
+    }
+
+    #[ink::test]
+    fn spending_limit() {
+        let accounts = default_accounts();
+        let config = Config {
+            spending_limit: Some(SpendingLimit {
+                amount: 15,
+                epoch_duration: 20,
+            }),
+            ..Default::default()
+        };
+        let mut dao =
+            create_membership_contract_with_config(vec![accounts.alice], 50, 1000, config);
+        assert_eq!(dao.get_epoch_spent(), 0);
+        assert_eq!(dao.get_epoch_remaining(), Some(15));
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        let _propose_result = dao.propose(accounts.django, 10, 10);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.vote(2, VoteType::Yes), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.get_epoch_spent(), 10);
+        assert_eq!(dao.get_epoch_remaining(), Some(5));
+        // The second proposal exceeds the limit of this epoch
+        assert_eq!(dao.execute(2), Err(Error::SpendingLimitExceeded));
+        assert_eq!(dao.get_treasury_amount(), 990);
+        // But not the limit of the next epoch
+        set_block_timestamp(20 * DAYS);
+        assert_eq!(dao.get_epoch_spent(), 0);
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(dao.get_epoch_remaining(), Some(5));
+        assert_eq!(dao.get_treasury_amount(), 980);
+    }
+
+    #[ink::test]
+    fn no_spending_limit() {
+        let governance_token: AccountId = [0x08; 32].into();
+        let dao = create_contract(governance_token, 10, 1000);
+        assert_eq!(dao.get_epoch_spent(), 0);
+        assert_eq!(dao.get_epoch_remaining(), None);
+    }
+
+    #[ink::test]
+    fn voting_rewards() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_reward: 30,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+            config,
+        );
+        // Fund the reward pool
+        set_sender(accounts.eve);
+        fund_rewards(&mut dao, 50);
+        assert_eq!(dao.get_reward_pool(), 50);
+        assert_eq!(dao.get_treasury_amount(), 1000);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        // The reward is reserved upon the first vote
+        assert_eq!(dao.get_proposal_reward(1), Ok(0));
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.get_proposal_reward(1), Ok(30));
+        assert_eq!(dao.get_reward_pool(), 20);
+        assert_eq!(
+            dao.get_vote(1, accounts.bob),
+            Some(VoteReceipt {
+                weight: 1,
+                vote_type: VoteType::Yes,
+            })
+        );
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(1, VoteType::No), Ok(()));
+        // The second proposal gets what is left of the reward pool
+        assert_eq!(dao.vote(2, VoteType::No), Ok(()));
+        assert_eq!(dao.get_proposal_reward(2), Ok(20));
+        assert_eq!(dao.get_reward_pool(), 0);
+        assert_eq!(dao.get_treasury_amount(), 1000);
+        // Rewards can be claimed when voting has ended
+        assert_eq!(dao.claim_voting_reward(1), Err(Error::ProposalStillActive));
+        set_block_timestamp(10 * DAYS);
+        let charlie_balance = get_balance(accounts.charlie);
+        assert_eq!(dao.claim_voting_reward(1), Ok(()));
+        assert_eq!(dao.claim_voting_reward(1), Err(Error::RewardAlreadyClaimed));
+        assert_eq!(dao.claim_voting_reward(2), Ok(()));
+        assert_eq!(get_balance(accounts.charlie), charlie_balance + 15 + 20);
+        // Only voters of proposals with a reward can claim
+        assert_eq!(dao.claim_voting_reward(3), Err(Error::NotVoted));
+        set_sender(accounts.django);
+        assert_eq!(dao.claim_voting_reward(1), Err(Error::NotVoted));
+        set_sender(accounts.bob);
+        let bob_balance = get_balance(accounts.bob);
+        assert_eq!(dao.claim_voting_reward(1), Ok(()));
+        assert_eq!(get_balance(accounts.bob), bob_balance + 15);
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn reward_pool_overflow() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob], 50, 1000);
+        dao.state.reward_pool = Balance::MAX;
+        set_value_transferred(1);
+        assert_eq!(dao.fund_rewards(), Err(Error::ArithmeticOverflow));
+        assert_eq!(dao.state.reward_pool, Balance::MAX);
+    }
+
+    #[ink::test]
+    fn voting_reward_dust() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_reward: 10,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+            config,
+        );
+        set_sender(accounts.eve);
+        fund_rewards(&mut dao, 10);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        for voter in [accounts.bob, accounts.charlie, accounts.django] {
+            set_sender(voter);
+            assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        }
+        set_block_timestamp(10 * DAYS);
+        set_sender(accounts.bob);
+        assert_eq!(dao.claim_voting_reward(1), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.claim_voting_reward(1), Ok(()));
+        assert_eq!(dao.get_reward_pool(), 0);
+        // The last claim releases the dust of 10 / 3
+        set_sender(accounts.django);
+        let django_balance = get_balance(accounts.django);
+        assert_eq!(dao.claim_voting_reward(1), Ok(()));
+        assert_eq!(get_balance(accounts.django), django_balance + 3);
+        assert_eq!(dao.get_reward_pool(), 1);
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn superseded_proposal_pays_no_reward() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_reward: 30,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie],
+            50,
+            1000,
+            config,
+        );
+        set_sender(accounts.eve);
+        fund_rewards(&mut dao, 30);
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.get_reward_pool(), 0);
+        // The amendment returns the reward to the pool
+        assert_eq!(dao.amend(1, accounts.frank, 10, 10), Ok(()));
+        assert_eq!(dao.get_reward_pool(), 30);
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.claim_voting_reward(1), Err(Error::ProposalSuperseded));
+        assert_eq!(dao.get_treasury_amount(), 1000);
+    }
+
+    #[ink::test]
+    fn no_voting_rewards() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob], 50, 1000);
+        set_sender(accounts.eve);
+        fund_rewards(&mut dao, 50);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.claim_voting_reward(1), Err(Error::NoVotingReward));
+        assert_eq!(dao.get_reward_pool(), 50);
+    }
+
+    #[ink::test]
+    fn approval_tiers() {
+        let accounts = default_accounts();
+        let members = vec![
+            accounts.bob,
+            accounts.charlie,
+            accounts.django,
+            accounts.eve,
+            accounts.frank,
+        ];
+        let high_tier = ApprovalTier {
+            min_amount: 100,
+            majority: Majority::TwoThirds,
+            quorum: 60,
+        };
+        let config = Config {
+            approval_tiers: vec![high_tier],
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(members, 20, 1000, config);
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.alice, 10, 10);
+        let _propose_result = dao.propose(accounts.alice, 100, 10);
+        let _propose_result = dao.propose(accounts.alice, 500, 10);
+        // Proposals below the lowest tier need a simple majority and the DAO's quorum
+        assert_eq!(
+            dao.get_approval_tier(1),
+            Ok(ApprovalTier {
+                min_amount: 0,
+                majority: Majority::Simple,
+                quorum: 20,
+            })
+        );
+        assert_eq!(dao.get_approval_tier(2), Ok(high_tier));
+        assert_eq!(dao.get_approval_tier(4), Err(Error::ProposalNotFound));
+        // Bob votes `Yes` on all, Charlie and Django on the third
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.vote(2, VoteType::Yes), Ok(()));
+        assert_eq!(dao.vote(3, VoteType::Yes), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(3, VoteType::Yes), Ok(()));
+        set_sender(accounts.django);
+        assert_eq!(dao.vote(3, VoteType::Yes), Ok(()));
+        // Eve and Frank vote `No` on the third
+        set_sender(accounts.eve);
+        assert_eq!(dao.vote(3, VoteType::No), Ok(()));
+        set_sender(accounts.frank);
+        assert_eq!(dao.vote(3, VoteType::No), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.execute(2), Err(Error::QuorumNotMet));
+        assert_eq!(dao.execute(3), Err(Error::SupermajorityNotReached));
+        // Optimistic proposals can't skip the requirements of a tier
+        assert_eq!(
+            dao.propose_optimistic(accounts.alice, 100, 10),
+            Err(Error::OptimisticAmountTooHigh)
+        );
+        assert_eq!(dao.propose_optimistic(accounts.alice, 99, 10), Ok(()));
+        assert_eq!(
+            dao.amend(4, accounts.alice, 100, 10),
+            Err(Error::OptimisticAmountTooHigh)
+        );
+    }
+
+    #[ink::test]
+    fn two_thirds_majority_works() {
+        let accounts = default_accounts();
+        let config = Config {
+            approval_tiers: vec![ApprovalTier {
+                min_amount: 100,
+                majority: Majority::TwoThirds,
+                quorum: 50,
+            }],
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+            config,
+        );
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 200, 10);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_sender(accounts.django);
+        assert_eq!(dao.vote(1, VoteType::No), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(get_balance(accounts.eve), 200);
+    }
+
+    #[ink::test]
+    fn voting_delay() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_delay: 2,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie],
+            50,
+            1000,
+            config,
+        );
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        let proposal = dao.get_proposal(1).unwrap();
+        assert_proposal(
+            proposal,
+            2 * DAYS,
+            accounts.eve,
+            10,
+            10,
+            ProposalKind::Standard,
+        );
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Pending));
+        assert_eq!(dao.vote(1, VoteType::Yes), Err(Error::VotingNotStarted));
+        set_block_timestamp(2 * DAYS);
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Active));
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        // Voting lasts the full duration after the delay
+        set_block_timestamp(11 * DAYS);
+        assert_eq!(dao.execute(1), Err(Error::ProposalStillActive));
+        set_block_timestamp(12 * DAYS);
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Succeeded));
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Executed));
+    }
+
+    #[ink::test]
+    fn voting_delay_overflow() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_delay: u64::MAX / DAYS + 1,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(vec![accounts.bob], 50, 1000, config);
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose(accounts.eve, 10, 10),
+            Err(Error::ArithmeticOverflow)
+        );
+        assert_eq!(dao.get_proposal(1), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn proposal_state_defeated() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Active));
+        assert_eq!(dao.vote(1, VoteType::No), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Defeated));
+        assert_eq!(dao.get_proposal_state(2), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn fund_records_contributions() {
+        let accounts = default_accounts();
+        set_value_transferred(100);
+        let mut dao = create_membership_contract(vec![accounts.bob], 50, 100);
+        assert_eq!(dao.get_contribution(accounts.alice), 100);
+        set_sender(accounts.bob);
+        set_value_transferred(30);
+        assert_eq!(dao.fund(), Ok(()));
+        assert_eq!(dao.fund(), Ok(()));
+        set_sender(accounts.charlie);
+        set_value_transferred(5);
+        assert_eq!(dao.fund(), Ok(()));
+        assert_eq!(dao.get_contribution(accounts.bob), 60);
+        assert_eq!(dao.get_contribution(accounts.charlie), 5);
+        assert_eq!(dao.get_contribution(accounts.django), 0);
+        assert_eq!(dao.get_total_inflow(), 165);
+        let events = get_events();
+        assert_eq!(events.len(), 4);
+        assert_fund_event(&events[0], accounts.alice, 100);
+        assert_fund_event(&events[3], accounts.charlie, 5);
+        // Funding without value is allowed by default
+        set_value_transferred(0);
+        assert_eq!(dao.fund(), Ok(()));
+        assert_eq!(dao.get_total_inflow(), 165);
+    }
+
+    #[ink::test]
+    fn reject_zero_funding() {
+        let accounts = default_accounts();
+        let config = Config {
+            reject_zero_funding: true,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(vec![accounts.bob], 50, 100, config);
+        set_sender(accounts.bob);
+        assert_eq!(dao.fund(), Err(Error::ZeroFunding));
+        set_value_transferred(1);
+        assert_eq!(dao.fund(), Ok(()));
+        assert_eq!(dao.get_contribution(accounts.bob), 1);
+    }
+
+    #[ink::test]
+    fn escrow_voting() {
+        let accounts = default_accounts();
+        let config = Config {
+            escrow_voting: true,
+            ..Default::default()
+        };
+        // Membership based DAO, nothing is escrowed
+        let mut dao =
+            create_membership_contract_with_config(vec![accounts.bob], 50, 100, config.clone());
+        set_sender(accounts.bob);
+        assert_eq!(dao.propose(accounts.eve, 10, 1), Ok(()));
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        assert_eq!(dao.get_escrow(1, accounts.bob), 0);
+
+        // Governance token based DAO, escrow tokens (hacky way)
+        let governance_token: AccountId = [0x08; 32].into();
+        let mut dao = Dao::new(governance_token, 10, VETO_THRESHOLD, Vec::new(), config);
+        assert_eq!(dao.propose(accounts.eve, 10, 1), Ok(()));
+        dao.votes.insert((1, accounts.bob), &());
+        dao.vote_receipts.insert(
+            (1, accounts.bob),
+            &VoteReceipt {
+                weight: 100,
+                vote_type: VoteType::Yes,
+            },
+        );
+        dao.escrows.insert((1, accounts.bob), &100);
+        assert_eq!(dao.get_escrow(1, accounts.bob), 100);
+        assert_eq!(dao.withdraw_vote_tokens(2), Err(Error::ProposalNotFound));
+        assert_eq!(dao.withdraw_vote_tokens(1), Err(Error::ProposalStillActive));
+        let proposal = dao.get_proposal(1).unwrap();
+        set_block_timestamp(proposal.end + 1);
+        set_sender(accounts.charlie);
+        assert_eq!(dao.withdraw_vote_tokens(1), Err(Error::NoEscrowedTokens));
+    }
+
+    #[ink::test]
+    fn reentrant_call_fails() {
+        let accounts = default_accounts();
+        set_sender(accounts.alice);
+        set_balance(contract_id(), 1000);
+        let mut dao = Dao::new_membership(
+            vec![accounts.bob],
+            50,
+            VETO_THRESHOLD,
+            vec![accounts.charlie, accounts.django],
+            Config::default(),
+        );
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 10, 10);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_block_timestamp(10 * DAYS);
+        // Pretend the DAO is in the middle of executing a proposal, a call back into
+        // the DAO is rejected by the lock alone
+        dao.reentrancy_lock.set(&true);
+        assert_eq!(dao.execute(1), Err(Error::ReentrantCall));
+        assert_eq!(dao.ragequit(10), Err(Error::ReentrantCall));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.veto(1), Err(Error::ReentrantCall));
+        assert_eq!(dao.get_vetoes(1), Ok(0));
+        assert!(!dao.get_proposal(1).map(|p| p.executed).unwrap_or(true));
+        // The same calls get past the lock once it is released
+        dao.reentrancy_lock.set(&false);
+        assert_eq!(dao.veto(1), Ok(()));
+        assert_eq!(dao.get_vetoes(1), Ok(1));
+        assert_eq!(dao.ragequit(10), Err(Error::NoGovernanceToken));
+        assert_eq!(dao.execute(1), Ok(()));
+        // The lock is released after every call
+        assert_eq!(dao.reentrancy_lock.get(), Some(false));
+        assert_eq!(get_balance(accounts.eve), 10);
+    }
+
+    #[ink::test]
+    fn committee_works() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        let spec = CommitteeSpec {
+            members: vec![accounts.charlie, accounts.django, accounts.eve],
+            budget: SpendingLimit {
+                amount: 50,
+                epoch_duration: 30,
+            },
+            quorum: 60,
+            voting_duration: 2,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_create_committee(spec.clone(), 2000, 10),
+            Err(Error::InvalidProposalAmount)
+        );
+        assert_eq!(dao.propose_create_committee(spec, 100, 10), Ok(()));
+        pass_proposal(&mut dao, 1);
+        assert_eq!(dao.execute(1), Ok(()));
+        let committee = dao.get_committee(0).unwrap();
+        assert_eq!(committee.allocation, 100);
+        assert_eq!(committee.total_members, 3);
+        assert_eq!(dao.get_treasury_amount(), 900);
+        assert!(dao.is_committee_member(0, accounts.django));
+        assert!(!dao.is_committee_member(0, accounts.bob));
+        // Only members can spend from the committee's allocation
+        assert_eq!(
+            dao.committee_propose(0, accounts.frank, 30),
+            Err(Error::NotCommitteeMember)
+        );
+        set_sender(accounts.django);
+        assert_eq!(
+            dao.committee_propose(0, accounts.frank, 101),
+            Err(Error::InsufficientCommitteeAllocation)
+        );
+        assert_eq!(dao.committee_propose(0, accounts.frank, 30), Ok(()));
+        assert_eq!(dao.committee_approve(0, 0), Err(Error::AlreadyApproved));
+        assert_eq!(
+            dao.committee_execute(0, 0),
+            Err(Error::CommitteeQuorumNotMet)
+        );
+        // The spend can be executed as soon as the quorum is met
+        set_sender(accounts.eve);
+        assert_eq!(dao.committee_approve(0, 0), Ok(()));
+        let frank_balance = get_balance(accounts.frank);
+        assert_eq!(dao.committee_execute(0, 0), Ok(()));
+        assert_eq!(get_balance(accounts.frank), frank_balance + 30);
+        assert_eq!(dao.committee_execute(0, 0), Err(Error::ProposalExecuted));
+        assert_eq!(dao.get_committee(0).unwrap().allocation, 70);
+        assert_eq!(dao.get_treasury_amount(), 900);
+        // The budget caps the spending per epoch
+        assert_eq!(dao.committee_propose(0, accounts.frank, 30), Ok(()));
+        set_sender(accounts.django);
+        assert_eq!(dao.committee_approve(0, 1), Ok(()));
+        assert_eq!(
+            dao.committee_execute(0, 1),
+            Err(Error::SpendingLimitExceeded)
+        );
+        // The approvals of a spend can't overflow
+        let mut spend = dao.committee_spends.get((0, 1)).unwrap();
+        spend.approvals = u32::MAX;
+        dao.committee_spends.insert((0, 1), &spend);
+        set_sender(accounts.charlie);
+        assert_eq!(dao.committee_approve(0, 1), Err(Error::ArithmeticOverflow));
+        // Spends expire after the voting duration
+        set_block_timestamp(get_block_timestamp() + 2 * DAYS);
+        assert_eq!(dao.committee_approve(0, 1), Err(Error::ProposalExpired));
+    }
+
+    #[ink::test]
+    fn committee_clawback_and_dissolve() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        let spec = CommitteeSpec {
+            members: vec![accounts.django],
+            budget: SpendingLimit {
+                amount: 100,
+                epoch_duration: 30,
+            },
+            quorum: 50,
+            voting_duration: 2,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_clawback_committee(0, 10, 10),
+            Err(Error::CommitteeNotFound)
+        );
+        assert_eq!(dao.propose_create_committee(spec, 100, 10), Ok(()));
+        pass_proposal(&mut dao, 1);
+        assert_eq!(dao.execute(1), Ok(()));
+        // Claw back part of the allocation
+        assert_eq!(dao.propose_clawback_committee(0, 60, 10), Ok(()));
+        pass_proposal(&mut dao, 2);
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(dao.get_committee(0).unwrap().allocation, 40);
+        assert_eq!(dao.get_treasury_amount(), 960);
+        // Dissolve the committee
+        assert_eq!(dao.propose_dissolve_committee(0, 10), Ok(()));
+        pass_proposal(&mut dao, 3);
+        assert_eq!(dao.execute(3), Ok(()));
+        assert_eq!(dao.get_committee(0), Err(Error::CommitteeNotFound));
+        assert!(!dao.is_committee_member(0, accounts.django));
+        assert_eq!(dao.get_treasury_amount(), 1000);
+        set_sender(accounts.django);
+        assert_eq!(
+            dao.committee_propose(0, accounts.frank, 10),
+            Err(Error::CommitteeNotFound)
+        );
+    }
+
+    #[ink::test]
+    fn recurring_payment_works() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        let schedule = RecurringSchedule {
+            interval: 30,
+            periods: 3,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_recurring_payment(
+                accounts.eve,
+                100,
+                RecurringSchedule {
+                    interval: 0,
+                    periods: 3
+                },
+                10
+            ),
+            Err(Error::InvalidRecurringPayment)
+        );
+        assert_eq!(
+            dao.propose_recurring_payment(accounts.eve, 100, schedule, 10),
+            Ok(())
+        );
+        assert_eq!(
+            dao.get_recurring_payment(1),
+            Err(Error::RecurringPaymentNotFound)
+        );
+        // Until the proposal is executed all periods are left
+        assert_eq!(dao.get_payments_left(1), Ok(3));
+        assert_eq!(
+            dao.get_payments_left(2),
+            Err(Error::RecurringPaymentNotFound)
+        );
+        pass_proposal(&mut dao, 1);
+        assert_eq!(dao.execute(1), Ok(()));
+        // The first payment can be claimed right away, by the recipient only
+        assert_eq!(dao.get_payments_left(1), Ok(3));
+        assert_eq!(dao.get_next_claim(1), Ok(10 * DAYS));
+        assert_eq!(dao.claim_recurring_payment(1), Err(Error::NotRecipient));
+        set_sender(accounts.eve);
+        assert_eq!(dao.claim_recurring_payment(1), Ok(()));
+        assert_eq!(dao.claim_recurring_payment(1), Err(Error::PaymentNotDue));
+        assert_eq!(dao.get_payments_left(1), Ok(2));
+        assert_eq!(dao.get_next_claim(1), Ok(40 * DAYS));
+        // Missed payments can be claimed later
+        set_block_timestamp(80 * DAYS);
+        assert_eq!(dao.claim_recurring_payment(1), Ok(()));
+        assert_eq!(dao.claim_recurring_payment(1), Ok(()));
+        assert_eq!(get_balance(accounts.eve), 300);
+        assert_eq!(dao.get_payments_left(1), Ok(0));
+        assert_eq!(
+            dao.claim_recurring_payment(1),
+            Err(Error::RecurringPaymentNotFound)
+        );
+        assert_eq!(dao.get_treasury_amount(), 700);
+    }
+
+    #[ink::test]
+    fn recurring_payment_interval_overflow() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        let schedule = RecurringSchedule {
+            interval: u64::MAX / DAYS + 1,
+            periods: 2,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_recurring_payment(accounts.eve, 100, schedule, 10),
+            Ok(())
+        );
+        pass_proposal(&mut dao, 1);
+        assert_eq!(dao.execute(1), Ok(()));
+        // The next claim after the first payment doesn't fit in a timestamp
+        set_sender(accounts.eve);
+        assert_eq!(
+            dao.claim_recurring_payment(1),
+            Err(Error::ArithmeticOverflow)
+        );
+        assert_eq!(dao.get_payments_left(1), Ok(2));
+    }
+
+    #[ink::test]
+    fn revoke_recurring_payment_works() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob, accounts.charlie], 50, 1000);
+        let schedule = RecurringSchedule {
+            interval: 30,
+            periods: 12,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_revoke_recurring_payment(1, 10),
+            Err(Error::RecurringPaymentNotFound)
+        );
+        assert_eq!(
+            dao.propose_recurring_payment(accounts.eve, 100, schedule, 10),
+            Ok(())
+        );
+        pass_proposal(&mut dao, 1);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(dao.propose_revoke_recurring_payment(1, 10), Ok(()));
+        pass_proposal(&mut dao, 2);
+        assert_eq!(dao.execute(2), Ok(()));
+        set_sender(accounts.eve);
+        assert_eq!(
+            dao.claim_recurring_payment(1),
+            Err(Error::RecurringPaymentNotFound)
+        );
+        assert_eq!(dao.get_payments_left(1), Ok(0));
+    }
+
+    #[ink::test]
+    fn recurring_payment_approval_tier() {
+        let accounts = default_accounts();
+        let config = Config {
+            approval_tiers: vec![ApprovalTier {
+                min_amount: 1000,
+                majority: Majority::TwoThirds,
+                quorum: 100,
+            }],
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie],
+            50,
+            1000,
+            config,
+        );
+        let schedule = RecurringSchedule {
+            interval: 30,
+            periods: 12,
+        };
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.propose_recurring_payment(accounts.eve, 100, schedule, 10),
+            Ok(())
+        );
+        // The tier is based on the total of all payments
+        assert_eq!(
+            dao.get_approval_tier(1).map(|tier| tier.majority),
+            Ok(Majority::TwoThirds)
+        );
+    }
+
+    #[ink::test]
+    fn amend_proposal() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+        );
+        set_sender(accounts.bob);
+        assert_eq!(dao.propose(accounts.eve, 100, 2), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        // Only the proposer can amend a transfer proposal
+        assert_eq!(dao.amend(1, accounts.frank, 10, 2), Err(Error::NotProposer));
+        assert_eq!(
+            dao.amend(2, accounts.frank, 10, 2),
+            Err(Error::ProposalNotFound)
+        );
+        assert_eq!(dao.propose_add_member(accounts.frank, 2), Ok(()));
+        assert_eq!(dao.amend(2, accounts.eve, 0, 2), Err(Error::NotAmendable));
+        set_sender(accounts.bob);
+        assert_eq!(
+            dao.amend(1, accounts.frank, 2000, 2),
+            Err(Error::InvalidProposalAmount)
+        );
+        // Fix the recipient and the amount
+        assert_eq!(dao.amend(1, accounts.frank, 10, 2), Ok(()));
+        assert_eq!(dao.get_proposal_state(1), Ok(ProposalState::Superseded));
+        assert_eq!(dao.get_proposal_state(3), Ok(ProposalState::Active));
+        assert_eq!(
+            dao.amend(1, accounts.frank, 20, 2),
+            Err(Error::ProposalSuperseded)
+        );
+        assert_eq!(dao.vote(1, VoteType::Yes), Err(Error::ProposalSuperseded));
+        // The votes on the original don't count for an amendment with another `to`
+        assert_eq!(dao.get_voters(3), Ok(0));
+        assert_eq!(dao.get_vote(3, accounts.charlie), None);
+        assert_eq!(dao.vote(3, VoteType::Yes), Ok(()));
+        // They carry over when the amendment only lowers the amount
+        assert_eq!(dao.amend(3, accounts.frank, 5, 2), Ok(()));
+        assert_eq!(dao.get_voters(4), Ok(1));
+        assert_eq!(
+            dao.get_vote(4, accounts.bob),
+            Some(VoteReceipt {
+                weight: 1,
+                vote_type: VoteType::Yes,
+            })
+        );
+        assert_eq!(dao.vote(4, VoteType::No), Err(Error::AlreadyVoted));
+        // But not when it raises the amount
+        assert_eq!(dao.amend(4, accounts.frank, 20, 2), Ok(()));
+        assert_eq!(dao.get_voters(5), Ok(0));
+        assert_eq!(dao.get_vote(5, accounts.bob), None);
+        assert_eq!(dao.get_amendment_chain(1), Ok(vec![1, 3, 4, 5]));
+        assert_eq!(dao.get_amendment_chain(5), Ok(vec![1, 3, 4, 5]));
+        assert_eq!(dao.get_amendment_chain(2), Ok(vec![2]));
+        assert_eq!(dao.get_amendment_chain(6), Err(Error::ProposalNotFound));
+        // Only the latest amendment can be executed
+        pass_proposal(&mut dao, 5);
+        assert_eq!(dao.execute(1), Err(Error::ProposalSuperseded));
+        assert_eq!(dao.execute(5), Ok(()));
+        assert_eq!(get_balance(accounts.frank), 20);
+    }
+
+    #[ink::test]
+    fn amendment_carries_votes() {
+        let accounts = default_accounts();
+        let config = Config {
+            voting_reward: 30,
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+            config.clone(),
+        );
+        set_sender(accounts.eve);
+        fund_rewards(&mut dao, 30);
+        set_sender(accounts.bob);
+        assert_eq!(dao.propose(accounts.eve, 100, 2), Ok(()));
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_sender(accounts.django);
+        assert_eq!(dao.vote(1, VoteType::No), Ok(()));
+        // Lower the amount and give the voters more time
+        set_sender(accounts.bob);
+        assert_eq!(dao.amend(1, accounts.eve, 80, 4), Ok(()));
+        assert_eq!(
+            dao.get_votes(2),
+            Ok(ProposalVotes {
+                total_yes: 1,
+                total_no: 1,
+            })
+        );
+        assert_eq!(dao.get_voters(2), Ok(2));
+        assert_eq!(
+            dao.get_vote(2, accounts.django),
+            Some(VoteReceipt {
+                weight: 1,
+                vote_type: VoteType::No,
+            })
+        );
+        // The voting reward moves along with the votes
+        assert_eq!(dao.get_proposal_reward(2), Ok(30));
+        assert_eq!(dao.get_reward_pool(), 0);
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(2, VoteType::No), Err(Error::AlreadyVoted));
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(2, VoteType::Yes), Ok(()));
+        let proposal = dao.get_proposal(2).unwrap();
+        set_block_timestamp(proposal.end);
+        assert_eq!(dao.get_proposal_state(2), Ok(ProposalState::Succeeded));
+        assert_eq!(dao.execute(2), Ok(()));
+        assert_eq!(get_balance(accounts.eve), 80);
+        // Every voter claims its share on the amendment
+        for voter in [accounts.bob, accounts.charlie, accounts.django] {
+            set_sender(voter);
+            assert_eq!(dao.claim_voting_reward(1), Err(Error::ProposalSuperseded));
+            assert_eq!(dao.claim_voting_reward(2), Ok(()));
+        }
+        assert_eq!(dao.get_reward_pool(), 0);
+        assert_eq!(dao.get_treasury_amount(), 920);
+    }
+
+    #[ink::test]
+    fn carried_vote_keeps_tokens_escrowed() {
+        let accounts = default_accounts();
+        let config = Config {
+            escrow_voting: true,
+            ..Default::default()
+        };
+        // Escrowed tokens back a carried vote until the amendment ends (hacky way)
+        let governance_token: AccountId = [0x08; 32].into();
+        let mut dao = Dao::new(governance_token, 10, VETO_THRESHOLD, Vec::new(), config);
+        assert_eq!(dao.propose(accounts.eve, 10, 1), Ok(()));
+        dao.votes.insert((1, accounts.bob), &());
+        dao.vote_receipts.insert(
+            (1, accounts.bob),
+            &VoteReceipt {
+                weight: 100,
+                vote_type: VoteType::Yes,
+            },
+        );
+        dao.escrows.insert((1, accounts.bob), &100);
+        assert_eq!(dao.amend(1, accounts.eve, 10, 3), Ok(()));
+        let proposal = dao.get_proposal(1).unwrap();
+        set_block_timestamp(proposal.end);
+        set_sender(accounts.bob);
+        assert_eq!(dao.withdraw_vote_tokens(1), Err(Error::ProposalStillActive));
+    }
+
+    #[ink::test]
+    fn simulate_execute_works() {
+        let accounts = default_accounts();
+        let config = Config {
+            spending_limit: Some(SpendingLimit {
+                amount: 100,
+                epoch_duration: 30,
+            }),
+            ..Default::default()
+        };
+        let mut dao = create_membership_contract_with_config(
+            vec![accounts.bob, accounts.charlie, accounts.django],
+            50,
+            1000,
+            config,
+        );
+        set_sender(accounts.bob);
+        let _propose_result = dao.propose(accounts.eve, 50, 10);
+        let _propose_result = dao.propose(accounts.eve, 200, 10);
+        let _propose_result = dao.propose_add_member(accounts.eve, 10);
+        assert_eq!(dao.simulate_execute(4), Err(Error::ProposalNotFound));
+        // Without votes the quorum is not met
+        let report = dao.simulate_execute(1).unwrap();
+        assert!(!report.voting_ended);
+        assert!(!report.quorum_met);
+        assert!(!report.majority_reached);
+        assert!(report.treasury_sufficient);
+        assert!(report.within_spending_limit);
+        assert_eq!(report.result, Err(Error::QuorumNotMet));
+        for proposal_id in 1..=3 {
+            set_sender(accounts.bob);
+            assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+        }
+        // Executing would succeed once voting has ended
+        assert_eq!(
+            dao.simulate_execute(1),
+            Ok(ExecutionReport {
+                executed: false,
+                vetoed: false,
+                superseded: false,
+                voting_ended: false,
+                approval_tier: ApprovalTier {
+                    min_amount: 0,
+                    majority: Majority::Simple,
+                    quorum: 50,
+                },
+                quorum_met: true,
+                majority_reached: true,
+                treasury_sufficient: true,
+                within_spending_limit: true,
+                target_valid: true,
+                result: Ok(()),
+            })
+        );
+        assert_eq!(dao.execute(1), Err(Error::ProposalStillActive));
+        // The second proposal exceeds the spending limit
+        let report = dao.simulate_execute(2).unwrap();
+        assert!(!report.within_spending_limit);
+        assert_eq!(report.result, Err(Error::SpendingLimitExceeded));
+        // The target of the third proposal is no longer valid when Eve became a member
+        dao.members.insert(accounts.eve, &());
+        let report = dao.simulate_execute(3).unwrap();
+        assert!(!report.target_valid);
+        assert_eq!(report.result, Err(Error::AlreadyMember));
+        // The simulation matches the execution
+        set_block_timestamp(10 * DAYS);
+        assert!(dao.simulate_execute(1).unwrap().voting_ended);
+        assert_eq!(dao.execute(1), Ok(()));
+        assert_eq!(
+            dao.simulate_execute(1).unwrap().result,
+            Err(Error::ProposalExecuted)
+        );
+        assert_eq!(dao.execute(2), Err(Error::SpendingLimitExceeded));
+        assert_eq!(dao.execute(3), Err(Error::AlreadyMember));
+    }
+
+    #[ink::test]
+    fn migrate_storage() {
+        let accounts = default_accounts();
+        let mut dao = create_membership_contract(vec![accounts.bob], 50, 1000);
+        assert_eq!(dao.get_storage_version(), 1);
+        // Anyone can migrate.
+        set_sender(accounts.bob);
+        assert_eq!(dao.migrate(), Err(Error::AlreadyMigrated));
+
+        // The code is replaced through a proposal.
+        let code_hash = Hash::from([0x01; 32]);
+        assert_eq!(dao.propose_set_code_hash(code_hash, 1), Ok(()));
+        let proposal = dao.get_proposal(1).unwrap();
+        assert_eq!(proposal.action, ProposalAction::SetCodeHash);
+        assert_eq!(proposal.to, contract_id());
+        assert_eq!(proposal.amount, 0);
+        assert_eq!(dao.code_hashes.get(1), Some(code_hash));
+        assert_eq!(dao.vote(1, VoteType::Yes), Ok(()));
+        set_block_timestamp(proposal.end);
+        assert_eq!(dao.simulate_execute(1).unwrap().result, Ok(()));
+    }
+
+    #[ink::test]
+    fn migrate_unversioned_storage() {
+        let accounts = default_accounts();
+        // The storage of the DAO from before versioning: the next proposal id, governance
+        // token and quorum in the root and the `Mapping`s in their own cells.
+        let proposals_key = KeyComposer::from_str("Dao::proposals");
+        let proposal_votes_key = KeyComposer::from_str("Dao::proposal_votes");
+        let votes_key = KeyComposer::from_str("Dao::votes");
+        let total_voters_key = KeyComposer::from_str("Dao::total_voters");
+        let root = scale::Encode::encode(&(2u64, accounts.django, 50u8));
+        ink::env::set_contract_storage(
+            &(proposals_key, 1u64),
+            &(accounts.eve, 10u128, 0u64, DAYS, true),
+        );
+        ink::env::set_contract_storage(
+            &(proposals_key, 2u64),
+            &(accounts.frank, 20u128, 0u64, 2 * DAYS, false),
+        );
+        ink::env::set_contract_storage(&(proposal_votes_key, 2u64), &(100u128, 0u128));
+        ink::env::set_contract_storage(&(votes_key, (2u64, accounts.bob)), &());
+        ink::env::set_contract_storage(&(total_voters_key, 1u64), &0u8);
+        ink::env::set_contract_storage(&(total_voters_key, 2u64), &1u8);
+
+        // The root is decoded from a buffer of its own length, like on chain.
+        let mut dao = <Dao as Storable>::decode(&mut &root[..]).unwrap();
+        assert_eq!(dao.get_storage_version(), 0);
+        assert_eq!(
+            dao.state.voting_source,
+            VotingSource::GovernanceToken(accounts.django)
+        );
+        assert_eq!(dao.state.quorum, 50);
+        assert_eq!(dao.migrate(), Ok(1));
+        assert_eq!(dao.migrate(), Err(Error::AlreadyMigrated));
+
+        // Read the migrated storage back.
+        let mut root = Vec::new();
+        dao.encode(&mut root);
+        let mut dao = <Dao as Storable>::decode(&mut &root[..]).unwrap();
+        assert_eq!(dao.get_storage_version(), 1);
+        assert_eq!(dao.next_proposal_id, 2);
+        assert_eq!(
+            dao.state.voting_source,
+            VotingSource::GovernanceToken(accounts.django)
+        );
+        assert_eq!(dao.state.quorum, 50);
+        assert_eq!(
+            dao.get_proposal(1),
+            Ok(Proposal {
+                to: accounts.eve,
+                amount: 10,
+                start: 0,
+                end: DAYS,
+                executed: true,
+                kind: ProposalKind::Standard,
+                action: ProposalAction::Transfer,
+                vetoed: false,
+            })
+        );
+        assert_eq!(dao.get_proposal(2).unwrap().to, accounts.frank);
+        assert_eq!(dao.get_voters(1), Ok(0));
+        assert_eq!(dao.get_voters(2), Ok(1));
+        assert_eq!(dao.get_votes(2).unwrap().total_yes, 100);
+        // A vote from before versioning counts, but has no receipt.
+        assert_eq!(dao.get_vote(2, accounts.bob), None);
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(2, VoteType::Yes), Err(Error::AlreadyVoted));
+    }
+
     // Helper functions for tests
     fn create_contract(governance_token: AccountId, quorum: u8, init_balance: Balance) -> Dao {
+        create_contract_with_guardians(governance_token, quorum, init_balance, Vec::new())
+    }
+
+    fn create_contract_with_guardians(
+        governance_token: AccountId,
+        quorum: u8,
+        init_balance: Balance,
+        guardians: Vec<AccountId>,
+    ) -> Dao {
         let accounts = default_accounts();
         set_sender(accounts.alice);
         set_balance(contract_id(), init_balance);
         debug_assert_eq!(get_balance(contract_id()), init_balance);
-        Dao::new(governance_token, quorum)
+        Dao::new(
+            governance_token,
+            quorum,
+            VETO_THRESHOLD,
+            guardians,
+            Config::default(),
+        )
+    }
+
+    fn create_membership_contract(
+        members: Vec<AccountId>,
+        quorum: u8,
+        init_balance: Balance,
+    ) -> Dao {
+        create_membership_contract_with_config(members, quorum, init_balance, Config::default())
+    }
+
+    fn create_membership_contract_with_config(
+        members: Vec<AccountId>,
+        quorum: u8,
+        init_balance: Balance,
+        config: Config,
+    ) -> Dao {
+        let accounts = default_accounts();
+        set_sender(accounts.alice);
+        set_balance(contract_id(), init_balance);
+        Dao::new_membership(members, quorum, VETO_THRESHOLD, Vec::new(), config)
+    }
+
+    // Let a proposal be accepted by the voters (hacky way).
+    fn accept_proposal(dao: &mut Dao, proposal_id: u64, quorum: u8) {
+        let proposal_votes = ProposalVotes {
+            total_yes: 100,
+            total_no: 0,
+        };
+        dao.proposal_votes.insert(proposal_id, &proposal_votes);
+        dao.total_voters.insert(proposal_id, &u32::from(quorum));
+    }
+
+    // Fund the reward pool, the transferred value is added to the contract balance.
+    fn fund_rewards(dao: &mut Dao, amount: Balance) {
+        set_balance(contract_id(), get_balance(contract_id()) + amount);
+        set_value_transferred(amount);
+        assert_eq!(dao.fund_rewards(), Ok(()));
+        set_value_transferred(0);
+    }
+
+    fn set_value_transferred(amount: Balance) {
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(amount);
+    }
+
+    // Let Bob and Charlie accept a proposal and let its voting period pass.
+    fn pass_proposal(dao: &mut Dao, proposal_id: u64) {
+        let accounts = default_accounts();
+        set_sender(accounts.charlie);
+        assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+        set_sender(accounts.bob);
+        assert_eq!(dao.vote(proposal_id, VoteType::Yes), Ok(()));
+        let proposal = dao.get_proposal(proposal_id).unwrap();
+        set_block_timestamp(proposal.end);
     }
 
     fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
@@ -188,9 +1735,13 @@ mod test {
             .unwrap_or_default()
     }
 
-    // fn get_events() -> Vec<ink::env::test::EmittedEvent> {
-    //     ink::env::test::recorded_events().collect::<Vec<_>>()
-    // }
+    fn get_events() -> Vec<ink::env::test::EmittedEvent> {
+        ink::env::test::recorded_events().collect::<Vec<_>>()
+    }
+
+    fn get_block_timestamp() -> Timestamp {
+        ink::env::block_timestamp::<ink::env::DefaultEnvironment>()
+    }
 
     fn set_block_timestamp(timestamp: Timestamp) {
         ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
@@ -214,12 +1765,45 @@ mod test {
     //     }
     // }
 
+    fn assert_fund_event(
+        event: &ink::env::test::EmittedEvent,
+        desired_from: AccountId,
+        desired_amount: Balance,
+    ) {
+        let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..]);
+        if let Ok(Event::Fund(Fund { from, amount })) = decoded_event {
+            assert_eq!(from, desired_from);
+            assert_eq!(amount, desired_amount);
+        } else {
+            panic!("Decoding of Fund event failed")
+        }
+    }
+
+    fn assert_proposal_vetoed_event(
+        event: &ink::env::test::EmittedEvent,
+        desired_proposal_id: u64,
+        desired_total_vetoes: u8,
+    ) {
+        let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..]);
+        if let Ok(Event::ProposalVetoed(ProposalVetoed {
+            proposal_id,
+            total_vetoes,
+        })) = decoded_event
+        {
+            assert_eq!(proposal_id, desired_proposal_id);
+            assert_eq!(total_vetoes, desired_total_vetoes);
+        } else {
+            panic!("Decoding of ProposalVetoed event failed")
+        }
+    }
+
     fn assert_proposal(
         proposal: Proposal,
         start: Timestamp,
         to: AccountId,
         amount: Balance,
         duration: Timestamp,
+        kind: ProposalKind,
     ) {
         assert_eq!(
             proposal,
@@ -229,6 +1813,9 @@ mod test {
                 start,
                 end: start + (duration * DAYS),
                 executed: false,
+                kind,
+                action: ProposalAction::Transfer,
+                vetoed: false,
             }
         )
     }
@@ -0,0 +1,462 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// The message enum generated for the `deploy_dao` arguments is much larger than for the
+// getters; it only lives for the decoding of a call.
+#[allow(clippy::large_enum_variant)]
+#[ink::contract]
+mod dao_factory {
+    use dao::{Config, DaoRef};
+    use erc20::{Erc20Ref, Role};
+    use ink::codegen::EmitEvent;
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::reflect::ContractEventBase;
+    use ink::storage::Mapping;
+    use ink::ToAccountId;
+    use token_traits::Erc20;
+
+    type Result<T> = core::result::Result<T, Error>;
+
+    // A DAO and its governance token, deployed by the factory.
+    #[derive(scale::Decode, scale::Encode, Debug, Copy, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct Deployment {
+        pub dao: AccountId,
+        pub governance_token: AccountId,
+        pub creator: AccountId,
+    }
+
+    // The metadata of a governance token deployed by the factory.
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq, Eq, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TokenMetadata {
+        // The name of the token, if any.
+        pub name: Option<String>,
+        // The symbol of the token, if any.
+        pub symbol: Option<String>,
+        // The number of decimals a balance is shown with.
+        pub decimals: u8,
+    }
+
+    // Contract storage.
+    #[ink(storage)]
+    pub struct DaoFactory {
+        // The code hash of the DAO contract.
+        pub dao_code_hash: Hash,
+        // The code hash of the governance token (erc20) contract.
+        pub token_code_hash: Hash,
+        // Store the deployments to their index.
+        pub deployments: Mapping<u32, Deployment>,
+        // The number of deployments.
+        pub total_deployments: u32,
+        // The genesis hash of the chain, the permits of the governance tokens are signed
+        // for it.
+        pub genesis_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct DaoDeployed {
+        #[ink(topic)]
+        pub dao: AccountId,
+        #[ink(topic)]
+        pub governance_token: AccountId,
+        pub creator: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        // At least one holder of the governance token is required.
+        NoHolders,
+        // Overflow.
+        ArithmeticOverflow,
+        // Instantiating the governance token failed.
+        TokenInstantiationFailed,
+        // Transferring the governance tokens to a holder failed.
+        TokenTransferFailed,
+        // Instantiating the DAO failed.
+        DaoInstantiationFailed,
+        // Handing the admin role of the governance token to the DAO failed.
+        TokenAdminTransferFailed,
+    }
+
+    impl DaoFactory {
+        #[ink(constructor)]
+        pub fn new(dao_code_hash: Hash, token_code_hash: Hash, genesis_hash: Hash) -> Self {
+            Self {
+                dao_code_hash,
+                token_code_hash,
+                deployments: Mapping::default(),
+                total_deployments: 0,
+                genesis_hash,
+            }
+        }
+
+        // Deploy a governance token with the `metadata`, with the supply minted to the
+        // `holders`, and a DAO governed by it. The transferred value funds the treasury of
+        // the DAO. Returns the account ids of the DAO and the governance token.
+        //
+        // The DAO becomes the admin of the token, so neither the factory nor the creator
+        // can mint or grant roles. No proposal action uses the role, which is intentional:
+        // the supply of the governance token stays what the holders got.
+        #[ink(message, payable)]
+        pub fn deploy_dao(
+            &mut self,
+            holders: Vec<(AccountId, Balance)>,
+            metadata: TokenMetadata,
+            quorum: u8,
+            veto_threshold: u8,
+            guardians: Vec<AccountId>,
+            config: Config,
+        ) -> Result<(AccountId, AccountId)> {
+            let total_supply = Self::total_supply(&holders)?;
+            let index = self.total_deployments;
+            self.total_deployments = index.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            // Every deployment gets its own salt, so the addresses are unique.
+            let salt = index.to_le_bytes();
+
+            // The supply is minted to the factory and then handed out to the holders.
+            let mut governance_token = match Erc20Ref::new(
+                total_supply,
+                metadata.name,
+                metadata.symbol,
+                metadata.decimals,
+                self.genesis_hash,
+            )
+            .code_hash(self.token_code_hash)
+            .endowment(0)
+            .salt_bytes(salt)
+            .try_instantiate()
+            {
+                Ok(Ok(governance_token)) => governance_token,
+                _ => return Err(Error::TokenInstantiationFailed),
+            };
+            for (holder, amount) in holders {
+                if governance_token.transfer(holder, amount).is_err() {
+                    return Err(Error::TokenTransferFailed);
+                }
+            }
+            let token_id = governance_token.to_account_id();
+
+            let dao = match DaoRef::new(token_id, quorum, veto_threshold, guardians, config)
+                .code_hash(self.dao_code_hash)
+                .endowment(self.env().transferred_value())
+                .salt_bytes(salt)
+                .try_instantiate()
+            {
+                Ok(Ok(dao)) => dao.to_account_id(),
+                _ => return Err(Error::DaoInstantiationFailed),
+            };
+
+            // The factory instantiated the token and so is its admin, it hands the role to
+            // the DAO and gives up its own.
+            let factory = self.env().account_id();
+            if governance_token.grant_role(Role::Admin, dao).is_err()
+                || governance_token.revoke_role(Role::Admin, factory).is_err()
+            {
+                return Err(Error::TokenAdminTransferFailed);
+            }
+
+            self.register(index, dao, token_id);
+            Ok((dao, token_id))
+        }
+
+        // The supply of the governance token, the sum of what the `holders` get.
+        #[inline]
+        fn total_supply(holders: &[(AccountId, Balance)]) -> Result<Balance> {
+            if holders.is_empty() {
+                return Err(Error::NoHolders);
+            }
+            holders.iter().try_fold(0, |total: Balance, (_, amount)| {
+                total.checked_add(*amount).ok_or(Error::ArithmeticOverflow)
+            })
+        }
+
+        // Record a deployment of the caller in the registry.
+        #[inline]
+        fn register(&mut self, index: u32, dao: AccountId, governance_token: AccountId) {
+            let creator = self.env().caller();
+            self.deployments.insert(
+                index,
+                &Deployment {
+                    dao,
+                    governance_token,
+                    creator,
+                },
+            );
+            self.emit_event(DaoDeployed {
+                dao,
+                governance_token,
+                creator,
+            });
+        }
+
+        // The events of the dao and erc20 dependencies make `self.env().emit_event`
+        // ambiguous.
+        #[inline]
+        fn emit_event<E>(&self, event: E)
+        where
+            E: Into<<DaoFactory as ContractEventBase>::Type>,
+        {
+            <ink::EnvAccess<'_, DefaultEnvironment> as EmitEvent<DaoFactory>>::emit_event(
+                self.env(),
+                event,
+            )
+        }
+
+        // Get a deployment by its index.
+        #[ink(message)]
+        pub fn get_deployment(&self, index: u32) -> Option<Deployment> {
+            self.deployments.get(index)
+        }
+
+        // Get the number of deployed DAOs.
+        #[ink(message)]
+        pub fn get_total_deployments(&self) -> u32 {
+            self.total_deployments
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        type Event = <DaoFactory as ContractEventBase>::Type;
+
+        #[ink::test]
+        fn new_works() {
+            let factory = DaoFactory::new(
+                Hash::from([1; 32]),
+                Hash::from([2; 32]),
+                Hash::from([3; 32]),
+            );
+            assert_eq!(factory.get_total_deployments(), 0);
+            assert_eq!(factory.get_deployment(0), None);
+        }
+
+        #[ink::test]
+        fn deploy_without_holders_fails() {
+            let mut factory = DaoFactory::new(
+                Hash::from([1; 32]),
+                Hash::from([2; 32]),
+                Hash::from([3; 32]),
+            );
+            assert_eq!(
+                factory.deploy_dao(
+                    Vec::new(),
+                    TokenMetadata::default(),
+                    10,
+                    20,
+                    Vec::new(),
+                    Config::default(),
+                ),
+                Err(Error::NoHolders)
+            );
+            assert_eq!(factory.get_total_deployments(), 0);
+        }
+
+        #[ink::test]
+        fn deploy_with_overflowing_supply_fails() {
+            let accounts = ink::env::test::default_accounts::<DefaultEnvironment>();
+            let mut factory = DaoFactory::new(
+                Hash::from([1; 32]),
+                Hash::from([2; 32]),
+                Hash::from([3; 32]),
+            );
+            let holders = vec![(accounts.bob, Balance::MAX), (accounts.charlie, 1)];
+            assert_eq!(
+                factory.deploy_dao(
+                    holders,
+                    TokenMetadata::default(),
+                    10,
+                    20,
+                    Vec::new(),
+                    Config::default(),
+                ),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(factory.get_total_deployments(), 0);
+        }
+
+        #[ink::test]
+        fn register_works() {
+            let accounts = ink::env::test::default_accounts::<DefaultEnvironment>();
+            let mut factory = DaoFactory::new(
+                Hash::from([1; 32]),
+                Hash::from([2; 32]),
+                Hash::from([3; 32]),
+            );
+            ink::env::test::set_caller::<DefaultEnvironment>(accounts.bob);
+            factory.register(0, accounts.django, accounts.eve);
+            let deployment = Deployment {
+                dao: accounts.django,
+                governance_token: accounts.eve,
+                creator: accounts.bob,
+            };
+            assert_eq!(factory.get_deployment(0), Some(deployment));
+            assert_eq!(factory.get_deployment(1), None);
+
+            // A `DaoDeployed` event is emitted for the deployment
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 1);
+            let Event::DaoDeployed(DaoDeployed {
+                dao,
+                governance_token,
+                creator,
+            }) = <Event as scale::Decode>::decode(&mut &events[0].data[..])
+                .expect("the event is a factory event");
+            assert_eq!(
+                Deployment {
+                    dao,
+                    governance_token,
+                    creator
+                },
+                deployment
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+        use token_traits::PSP22Metadata;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn deploy_dao_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Upload the dao and erc20 contracts
+            let dao_code_hash = client
+                .upload("dao", &ink_e2e::alice(), None)
+                .await
+                .expect("upload dao failed")
+                .code_hash;
+            let token_code_hash = client
+                .upload("erc20", &ink_e2e::alice(), None)
+                .await
+                .expect("upload erc20 failed")
+                .code_hash;
+            // Instantiate the factory
+            let factory_constructor =
+                DaoFactoryRef::new(dao_code_hash, token_code_hash, Hash::default());
+            let factory_id = client
+                .instantiate(
+                    "dao_factory",
+                    &ink_e2e::alice(),
+                    factory_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // Deploy a DAO with Bob and Charlie as token holders
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let holders = vec![(bob_account, 600), (charlie_account, 400)];
+            let metadata = TokenMetadata {
+                name: Some(String::from("Governance")),
+                symbol: Some(String::from("GOV")),
+                decimals: 10,
+            };
+            let deploy = build_message::<DaoFactoryRef>(factory_id.clone()).call(|factory| {
+                factory.deploy_dao(
+                    holders.clone(),
+                    metadata.clone(),
+                    10,
+                    20,
+                    Vec::new(),
+                    Config::default(),
+                )
+            });
+            let deploy_res = client
+                .call(&ink_e2e::alice(), deploy, 100, None)
+                .await
+                .expect("deploy failed");
+            let (dao_id, governance_token) = deploy_res.return_value().unwrap();
+
+            // The deployment is registered
+            let get_deployment = build_message::<DaoFactoryRef>(factory_id.clone())
+                .call(|factory| factory.get_deployment(0));
+            let get_deployment_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_deployment, 0, None)
+                .await;
+            assert_eq!(
+                get_deployment_res.return_value(),
+                Some(Deployment {
+                    dao: dao_id,
+                    governance_token,
+                    creator: ink_e2e::account_id(ink_e2e::AccountKeyring::Alice),
+                })
+            );
+
+            // The holders got their governance tokens
+            let balance_of = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(balance_of_res.return_value(), 600);
+            let balance_of = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.balance_of(charlie_account));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(balance_of_res.return_value(), 400);
+            // The factory kept none of the supply
+            let balance_of = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.balance_of(factory_id));
+            let balance_of_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(balance_of_res.return_value(), 0);
+            let total_supply = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.total_supply());
+            let total_supply_res = client
+                .call_dry_run(&ink_e2e::alice(), &total_supply, 0, None)
+                .await;
+            assert_eq!(total_supply_res.return_value(), 1000);
+
+            // The governance token has the metadata
+            let token_symbol = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.token_symbol());
+            let token_symbol_res = client
+                .call_dry_run(&ink_e2e::alice(), &token_symbol, 0, None)
+                .await;
+            assert_eq!(token_symbol_res.return_value(), Some(String::from("GOV")));
+            let token_decimals = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.token_decimals());
+            let token_decimals_res = client
+                .call_dry_run(&ink_e2e::alice(), &token_decimals, 0, None)
+                .await;
+            assert_eq!(token_decimals_res.return_value(), 10);
+
+            // The DAO is the admin of the governance token, the factory is not
+            let has_role = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.has_role(Role::Admin, dao_id));
+            let has_role_res = client
+                .call_dry_run(&ink_e2e::alice(), &has_role, 0, None)
+                .await;
+            assert!(has_role_res.return_value());
+            let has_role = build_message::<Erc20Ref>(governance_token.clone())
+                .call(|erc20| erc20.has_role(Role::Admin, factory_id));
+            let has_role_res = client
+                .call_dry_run(&ink_e2e::alice(), &has_role, 0, None)
+                .await;
+            assert!(!has_role_res.return_value());
+
+            // The treasury of the DAO is funded
+            let get_treasury_amount =
+                build_message::<DaoRef>(dao_id.clone()).call(|dao| dao.get_treasury_amount());
+            let get_treasury_amount_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_treasury_amount, 0, None)
+                .await;
+            assert_eq!(get_treasury_amount_res.return_value(), 100);
+            Ok(())
+        }
+    }
+}
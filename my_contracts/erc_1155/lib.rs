@@ -1,72 +1,302 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use self::erc_1155::{Erc1155, Erc1155Ref};
+
 #[ink::contract]
 mod erc_1155 {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use token_traits::{Erc1155Error, TokenId};
+
+    type Result<T> = core::result::Result<T, Erc1155Error>;
 
-    /// Defines the storage of your contract.
-    /// Add new fields to the below struct in order
-    /// to add new static storage fields to your contract.
+    /// A multi token contract: every token id has its own supply and balances.
     #[ink(storage)]
+    #[derive(Default)]
     pub struct Erc1155 {
-        /// Stores a single `bool` value on the storage.
-        value: bool,
+        /// Mapping from (owner, token id) to the balance of the owner.
+        balances: Mapping<(AccountId, TokenId), Balance>,
+        /// Mapping from token id to its total supply.
+        supplies: Mapping<TokenId, Balance>,
+        /// Mapping from token id to the account that created it.
+        creators: Mapping<TokenId, AccountId>,
+        /// Mapping from (owner, operator) to whether the operator may transfer all
+        /// tokens of the owner.
+        approvals: Mapping<(AccountId, AccountId), ()>,
+        /// The id of the last created token.
+        last_token_id: TokenId,
+    }
+
+    /// Event emitted when tokens are minted or transferred.
+    #[ink(event)]
+    pub struct TransferSingle {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        token_id: TokenId,
+        value: Balance,
+    }
+
+    /// Event emitted when an operator is allowed or disallowed to transfer all tokens
+    /// of an owner.
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
     }
 
     impl Erc1155 {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
+        /// Creates a new ERC-1155 contract without tokens.
         #[ink(constructor)]
-        pub fn new(init_value: bool) -> Self {
-            Self { value: init_value }
+        pub fn new() -> Self {
+            Default::default()
         }
 
-        /// Constructor that initializes the `bool` value to `false`.
+        /// Creates a new token with an initial supply of `value` for the caller, who
+        /// becomes its creator. Returns the id of the token.
+        #[ink(message)]
+        pub fn create(&mut self, value: Balance) -> TokenId {
+            let caller = self.env().caller();
+            self.last_token_id += 1;
+            let token_id = self.last_token_id;
+            self.creators.insert(token_id, &caller);
+            self.mint_to(caller, token_id, value)
+                .expect("a new token has no supply to overflow");
+            token_id
+        }
+
+        /// Mints `value` of the token `token_id` to the caller.
         ///
-        /// Constructors can delegate to other constructors.
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self::new(Default::default())
+        /// # Errors
+        ///
+        /// Returns `TokenNotFound` if the token does not exist, `NotApproved` if the
+        /// caller is not the creator of the token and `SupplyOverflow` if the supply
+        /// would overflow.
+        #[ink(message)]
+        pub fn mint(&mut self, token_id: TokenId, value: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            match self.creators.get(token_id) {
+                Some(creator) if creator == caller => {}
+                Some(_) => return Err(Erc1155Error::NotApproved),
+                None => return Err(Erc1155Error::TokenNotFound),
+            }
+            self.mint_to(caller, token_id, value)
         }
 
-        /// A message that can be called on instantiated contracts.
-        /// This one flips the value of the stored `bool` from `true`
-        /// to `false` and vice versa.
+        fn mint_to(&mut self, to: AccountId, token_id: TokenId, value: Balance) -> Result<()> {
+            let supply = self
+                .supplies
+                .get(token_id)
+                .unwrap_or_default()
+                .checked_add(value)
+                .ok_or(Erc1155Error::SupplyOverflow)?;
+            let balance = self
+                .balances
+                .get((to, token_id))
+                .unwrap_or_default()
+                .checked_add(value)
+                .ok_or(Erc1155Error::SupplyOverflow)?;
+            self.balances.insert((to, token_id), &balance);
+            self.supplies.insert(token_id, &supply);
+            self.env().emit_event(TransferSingle {
+                operator: to,
+                from: None,
+                to: Some(to),
+                token_id,
+                value,
+            });
+            Ok(())
+        }
+    }
+
+    impl token_traits::Erc1155 for Erc1155 {
+        /// Returns the balance of `owner` for the token `token_id`.
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId, token_id: TokenId) -> Balance {
+            self.balances.get((owner, token_id)).unwrap_or_default()
+        }
+
+        /// Returns the balances of the (`owner`, `token_id`) pairs, up to the shortest of
+        /// both lists.
+        #[ink(message)]
+        fn balance_of_batch(
+            &self,
+            owners: Vec<AccountId>,
+            token_ids: Vec<TokenId>,
+        ) -> Vec<Balance> {
+            owners
+                .into_iter()
+                .zip(token_ids)
+                .map(|(owner, token_id)| self.balances.get((owner, token_id)).unwrap_or_default())
+                .collect()
+        }
+
+        /// Returns the total supply of the token `token_id`.
         #[ink(message)]
-        pub fn flip(&mut self) {
-            self.value = !self.value;
+        fn total_supply(&self, token_id: TokenId) -> Balance {
+            self.supplies.get(token_id).unwrap_or_default()
         }
 
-        /// Simply returns the current value of our `bool`.
+        /// Transfers `value` of the token `token_id` from `from` to `to`.
+        ///
+        /// On success a `TransferSingle` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotApproved` if the caller is not `from` nor an approved operator
+        /// of `from`, and `InsufficientBalance` if `from` does not hold `value` of the
+        /// token.
         #[ink(message)]
-        pub fn get(&self) -> bool {
-            self.value
+        fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            token_id: TokenId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != from && !self.approvals.contains((from, caller)) {
+                return Err(Erc1155Error::NotApproved);
+            }
+            let from_balance = self.balances.get((from, token_id)).unwrap_or_default();
+            if from_balance < value {
+                return Err(Erc1155Error::InsufficientBalance);
+            }
+            self.balances
+                .insert((from, token_id), &(from_balance - value));
+            let to_balance = self.balances.get((to, token_id)).unwrap_or_default();
+            self.balances.insert((to, token_id), &(to_balance + value));
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: Some(from),
+                to: Some(to),
+                token_id,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Allows or disallows `operator` to transfer all tokens of the caller.
+        ///
+        /// An `ApprovalForAll` event is emitted.
+        #[ink(message)]
+        fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            let owner = self.env().caller();
+            if approved {
+                self.approvals.insert((owner, operator), &());
+            } else {
+                self.approvals.remove((owner, operator));
+            }
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Returns whether `operator` may transfer all tokens of `owner`.
+        #[ink(message)]
+        fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.approvals.contains((owner, operator))
         }
     }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
     #[cfg(test)]
     mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use token_traits::Erc1155 as _;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        }
 
-        /// We test if the default constructor does its job.
         #[ink::test]
-        fn default_works() {
-            let erc_1155 = Erc1155::default();
-            assert_eq!(erc_1155.get(), false);
+        fn create_and_mint_works() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut erc_1155 = Erc1155::new();
+            assert_eq!(erc_1155.create(100), 1);
+            assert_eq!(erc_1155.create(5), 2);
+            assert_eq!(erc_1155.balance_of(accounts.alice, 1), 100);
+            assert_eq!(erc_1155.total_supply(2), 5);
+            assert_eq!(erc_1155.mint(2, 5), Ok(()));
+            assert_eq!(erc_1155.total_supply(2), 10);
+            assert_eq!(erc_1155.mint(3, 5), Err(Erc1155Error::TokenNotFound));
+            assert_eq!(
+                erc_1155.mint(2, Balance::MAX),
+                Err(Erc1155Error::SupplyOverflow)
+            );
+            assert_eq!(erc_1155.total_supply(2), 10);
+            // Only the creator can mint
+            set_caller(accounts.bob);
+            assert_eq!(erc_1155.mint(1, 5), Err(Erc1155Error::NotApproved));
+            assert_eq!(
+                erc_1155.balance_of_batch(vec![accounts.alice, accounts.alice], vec![1, 2]),
+                vec![100, 10]
+            );
         }
 
-        /// We test a simple use case of our contract.
         #[ink::test]
-        fn it_works() {
-            let mut erc_1155 = Erc1155::new(false);
-            assert_eq!(erc_1155.get(), false);
-            erc_1155.flip();
-            assert_eq!(erc_1155.get(), true);
+        fn transfer_works() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut erc_1155 = Erc1155::new();
+            let token_id = erc_1155.create(100);
+            assert_eq!(
+                erc_1155.safe_transfer_from(
+                    accounts.alice,
+                    accounts.bob,
+                    token_id,
+                    101,
+                    Vec::new()
+                ),
+                Err(Erc1155Error::InsufficientBalance)
+            );
+            assert_eq!(
+                erc_1155.safe_transfer_from(accounts.alice, accounts.bob, token_id, 40, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(erc_1155.balance_of(accounts.alice, token_id), 60);
+            assert_eq!(erc_1155.balance_of(accounts.bob, token_id), 40);
+            assert_eq!(erc_1155.total_supply(token_id), 100);
         }
-    }
 
+        #[ink::test]
+        fn approval_for_all_works() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut erc_1155 = Erc1155::new();
+            let token_id = erc_1155.create(100);
+            set_caller(accounts.bob);
+            assert_eq!(
+                erc_1155.safe_transfer_from(accounts.alice, accounts.bob, token_id, 10, Vec::new()),
+                Err(Erc1155Error::NotApproved)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(erc_1155.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert!(erc_1155.is_approved_for_all(accounts.alice, accounts.bob));
+            set_caller(accounts.bob);
+            assert_eq!(
+                erc_1155.safe_transfer_from(accounts.alice, accounts.bob, token_id, 10, Vec::new()),
+                Ok(())
+            );
+            set_caller(accounts.alice);
+            assert_eq!(erc_1155.set_approval_for_all(accounts.bob, false), Ok(()));
+            assert!(!erc_1155.is_approved_for_all(accounts.alice, accounts.bob));
+        }
+    }
 
     /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
     ///
@@ -77,6 +307,7 @@ mod erc_1155 {
     mod e2e_tests {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use token_traits::Erc1155 as _;
 
         /// A helper function used for calling contract messages.
         use ink_e2e::build_message;
@@ -84,57 +315,49 @@ mod erc_1155 {
         /// The End-to-End test `Result` type.
         type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-        /// We test that we can upload and instantiate the contract using its default constructor.
+        /// We test that a created token can be transferred.
         #[ink_e2e::test]
-        async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn create_and_transfer_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // Given
-            let constructor = Erc1155Ref::default();
-
-            // When
+            let constructor = Erc1155Ref::new();
             let contract_account_id = client
                 .instantiate("erc_1155", &ink_e2e::alice(), constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
-
-            // Then
-            let get = build_message::<Erc1155Ref>(contract_account_id.clone())
-                .call(|erc_1155| erc_1155.get());
-            let get_result = client.call_dry_run(&ink_e2e::alice(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), false));
-
-            Ok(())
-        }
-
-        /// We test that we can read and write a value from the on-chain contract contract.
-        #[ink_e2e::test]
-        async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-            // Given
-            let constructor = Erc1155Ref::new(false);
-            let contract_account_id = client
-                .instantiate("erc_1155", &ink_e2e::bob(), constructor, 0, None)
+            let create = build_message::<Erc1155Ref>(contract_account_id.clone())
+                .call(|erc_1155| erc_1155.create(100));
+            let create_result = client
+                .call(&ink_e2e::alice(), create, 0, None)
                 .await
-                .expect("instantiate failed")
-                .account_id;
-
-            let get = build_message::<Erc1155Ref>(contract_account_id.clone())
-                .call(|erc_1155| erc_1155.get());
-            let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), false));
+                .expect("create failed");
+            let token_id = create_result.return_value();
 
             // When
-            let flip = build_message::<Erc1155Ref>(contract_account_id.clone())
-                .call(|erc_1155| erc_1155.flip());
-            let _flip_result = client
-                .call(&ink_e2e::bob(), flip, 0, None)
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer =
+                build_message::<Erc1155Ref>(contract_account_id.clone()).call(|erc_1155| {
+                    erc_1155.safe_transfer_from(
+                        alice_account,
+                        bob_account,
+                        token_id,
+                        40,
+                        Vec::new(),
+                    )
+                });
+            let _transfer_result = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
                 .await
-                .expect("flip failed");
+                .expect("transfer failed");
 
             // Then
-            let get = build_message::<Erc1155Ref>(contract_account_id.clone())
-                .call(|erc_1155| erc_1155.get());
-            let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), true));
+            let balance_of = build_message::<Erc1155Ref>(contract_account_id.clone())
+                .call(|erc_1155| erc_1155.balance_of(bob_account, token_id));
+            let balance_of_result = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await;
+            assert_eq!(balance_of_result.return_value(), 40);
 
             Ok(())
         }
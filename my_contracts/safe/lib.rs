@@ -2,11 +2,24 @@
 
 #[ink::contract]
 mod safe {
-    use ink::storage::Mapping;
+    use ink::storage::traits::ManualKey;
+    use ink::storage::{Lazy, Mapping};
+
+    // The version of the storage layout. The deposits kept their cell, so version 1
+    // only adds the version itself and the admin.
+    const STORAGE_VERSION: u32 = 1;
+    // The storage key of the storage version.
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
 
     #[ink(storage)]
     pub struct Safe {
         deposits: Mapping<AccountId, Balance>,
+        // The account that can replace the code of the safe, see `fn set_code`. A safe
+        // from before versioning has none.
+        admin: Lazy<AccountId>,
+        // The version of the storage layout, see `fn migrate`. Storage written before
+        // versioning has no version (version 0).
+        storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
     }
 
     #[ink(event)]
@@ -46,6 +59,12 @@ mod safe {
         InsufficientBalance,
         /// Returned due to unknown account.
         UnknownAccount,
+        /// Returned if the storage is already at the current storage version.
+        AlreadyMigrated,
+        /// Returned if the caller is not the admin.
+        NotAdmin,
+        /// Returned if no contract code has been uploaded with the code hash.
+        CodeNotFound,
         // TODO: Overflow.
     }
 
@@ -54,8 +73,14 @@ mod safe {
     impl Safe {
         #[ink(constructor)]
         pub fn default() -> Self {
+            let mut admin = Lazy::new();
+            admin.set(&Self::env().caller());
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
             Self {
                 deposits: Mapping::default(),
+                admin,
+                storage_version,
             }
         }
 
@@ -148,6 +173,37 @@ mod safe {
             self.env().balance()
         }
 
+        // Replace the code of the safe with the code uploaded with `code_hash`, the admin
+        // only. The new code takes effect from the next call, which is `fn migrate`.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            if self.admin.get() != Some(self.env().caller()) {
+                return Err(Error::NotAdmin);
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::CodeNotFound)
+        }
+
+        // Move the storage forward to the current storage version and return it. Anyone
+        // can migrate, the steps that run are those of the code set by the admin or, for
+        // a safe from before versioning (version 0), by the chain. That safe only stored
+        // the deposits, so it just gets its version.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<u32> {
+            if self.get_storage_version() >= STORAGE_VERSION {
+                return Err(Error::AlreadyMigrated);
+            }
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(STORAGE_VERSION)
+        }
+
+        // Returns the version of the storage layout.
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+
         // For showcase purposes of a contract in unit testing having the same
         // AccountId as the contract caller.
         #[ink(message)]
@@ -159,6 +215,7 @@ mod safe {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use ink::storage::traits::StorageKey;
 
         #[ink::test]
         fn account_id() {
@@ -332,6 +389,37 @@ mod safe {
             assert_eq!(safe.total(), 150);
         }
 
+        #[ink::test]
+        fn migrate_storage() {
+            let accounts = default_accounts();
+            let mut safe = create_contract();
+            assert_eq!(safe.get_storage_version(), 1);
+            // Anyone can migrate, only the admin can replace the code.
+            set_sender(accounts.bob);
+            assert_eq!(safe.migrate(), Err(Error::AlreadyMigrated));
+            assert_eq!(safe.set_code(Hash::from([0x01; 32])), Err(Error::NotAdmin));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            safe.deposit();
+
+            // Encode the storage of a deployment from before versioning: the deposits
+            // are in their own cells, so the storage root is empty, and there is no
+            // storage version.
+            let root_key = <Safe as StorageKey>::KEY;
+            ink::env::set_contract_storage(&root_key, &());
+            ink::env::clear_contract_storage(&STORAGE_VERSION_KEY);
+            let mut safe: Safe = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(safe.get_storage_version(), 0);
+            assert_eq!(safe.migrate(), Ok(1));
+            assert_eq!(safe.migrate(), Err(Error::AlreadyMigrated));
+
+            // Read it back
+            ink::env::set_contract_storage(&root_key, &safe);
+            let safe: Safe = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(safe.get_storage_version(), 1);
+            set_sender(accounts.bob);
+            assert_eq!(safe.get(), 100);
+        }
+
         fn create_contract() -> Safe {
             let accounts = default_accounts();
             set_sender(accounts.alice);
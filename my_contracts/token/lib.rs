@@ -2,28 +2,15 @@
 
 #[ink::contract]
 mod token {
-    use ink::storage::Mapping;
+    use ink::prelude::string::String;
+    use ink::storage::traits::ManualKey;
+    use ink::storage::{Lazy, Mapping};
+    use token_traits::{Erc20, Erc20Allowance, Erc20Permit, Error, PSP22Metadata, Result};
 
-    #[ink::trait_definition]
-    pub trait Erc20 {
-        #[ink(message)]
-        fn total_supply(&self) -> Balance;
-
-        #[ink(message)]
-        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()>;
-
-        #[ink(message)]
-        fn balance_of(&self, account: AccountId) -> Balance;
-
-        #[ink(message)]
-        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()>;
-
-        #[ink(message)]
-        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
-
-        #[ink(message)]
-        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()>;
-    }
+    // The version of the storage layout, see `migrate`. Version 1 added the permit nonces,
+    // metadata and admin in their own cells, so the storage root is still the total supply.
+    const STORAGE_VERSION: u32 = 1;
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
 
     #[ink(storage)]
     #[derive(Default)]
@@ -31,6 +18,23 @@ mod token {
         total_supply: Balance,
         balances: Mapping<AccountId, Balance>,
         allowances: Mapping<(AccountId, AccountId), Balance>,
+        storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
+        nonces: Mapping<AccountId, u64>,
+        name: Lazy<String>,
+        symbol: Lazy<String>,
+        decimals: Lazy<u8>,
+        genesis_hash: Lazy<Hash>,
+        // The account that can replace the code of the token, see `set_code`. A token from
+        // before versioning has none.
+        admin: Lazy<AccountId>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum MigrationError {
+        AlreadyMigrated,
+        NotAdmin,
+        CodeNotFound,
     }
 
     #[ink(event)]
@@ -51,20 +55,15 @@ mod token {
         value: Balance,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub enum Error {
-        /// Returned if not enough balance to fulfill a request is available.
-        InsufficientBalance,
-        /// Returned if not enough allowance to fulfill a request is available.
-        InsufficientAllowance,
-    }
-
-    pub type Result<T> = core::result::Result<T, Error>;
-
     impl Token {
         #[ink(constructor)]
-        pub fn new(total_supply: Balance) -> Self {
+        pub fn new(
+            total_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            genesis_hash: Hash,
+        ) -> Self {
             let mut balances = Mapping::default();
             let caller = Self::env().caller();
             balances.insert(caller, &total_supply);
@@ -73,10 +72,73 @@ mod token {
                 to: Some(caller),
                 value: total_supply,
             });
-            Self {
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+            let mut token = Self {
                 total_supply,
                 balances,
                 allowances: Default::default(),
+                storage_version,
+                nonces: Default::default(),
+                name: Default::default(),
+                symbol: Default::default(),
+                decimals: Default::default(),
+                genesis_hash: Default::default(),
+                admin: Default::default(),
+            };
+            if let Some(name) = name {
+                token.name.set(&name);
+            }
+            if let Some(symbol) = symbol {
+                token.symbol.set(&symbol);
+            }
+            token.decimals.set(&decimals);
+            token.genesis_hash.set(&genesis_hash);
+            token.admin.set(&caller);
+            token
+        }
+
+        // Replaces the code of the token with the code uploaded with `code_hash`, the
+        // admin only. The new code takes effect from the next call, which is `migrate`.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> core::result::Result<(), MigrationError> {
+            self.admin_only()?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| MigrationError::CodeNotFound)
+        }
+
+        // Moves the storage forward to the current storage version, the admin only. A
+        // token from before versioning (version 0) got its code from the chain and has no
+        // admin, so anyone can migrate it. It keeps its balances and allowances, gets no
+        // metadata and signs the first permit of every owner with nonce 0, for the chain
+        // with `genesis_hash`.
+        #[ink(message)]
+        pub fn migrate(&mut self, genesis_hash: Hash) -> core::result::Result<u32, MigrationError> {
+            self.admin_only()?;
+            if self.storage_version() >= STORAGE_VERSION {
+                return Err(MigrationError::AlreadyMigrated);
+            }
+            self.genesis_hash.set(&genesis_hash);
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(STORAGE_VERSION)
+        }
+
+        // The genesis hash of the chain the permits are signed for.
+        #[ink(message)]
+        pub fn genesis_hash(&self) -> Hash {
+            self.genesis_hash.get().unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+
+        fn admin_only(&self) -> core::result::Result<(), MigrationError> {
+            match self.admin.get() {
+                Some(admin) if admin != self.env().caller() => Err(MigrationError::NotAdmin),
+                _ => Ok(()),
             }
         }
     }
@@ -122,6 +184,79 @@ mod token {
         }
     }
 
+    impl PSP22Metadata for Token {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.get()
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.get()
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals.get().unwrap_or_default()
+        }
+    }
+
+    impl Erc20Allowance for Token {
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance_impl(&caller, &spender);
+            self.approve_from_to(&caller, &spender, allowance.saturating_add(delta_value))
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let allowance = self.allowance_impl(&caller, &spender);
+            if allowance < delta_value {
+                return Err(Error::InsufficientAllowance);
+            }
+            self.approve_from_to(&caller, &spender, allowance - delta_value)
+        }
+    }
+
+    impl Erc20Permit for Token {
+        #[ink(message)]
+        fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+            let nonce = self.nonces(owner);
+            let hash = token_traits::permit_hash(
+                self.genesis_hash(),
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            );
+            if token_traits::recover_signer(&signature, &hash) != Some(owner) {
+                return Err(Error::InvalidSignature);
+            }
+            let next_nonce = nonce.checked_add(1).ok_or(Error::NonceOverflow)?;
+            self.nonces.insert(owner, &next_nonce);
+            self.approve_from_to(&owner, &spender, value)
+        }
+
+        #[ink(message)]
+        fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or_default()
+        }
+    }
+
     #[ink(impl)]
     impl Token {
         #[inline]
@@ -161,13 +296,7 @@ mod token {
             spender: &AccountId,
             value: Balance,
         ) -> Result<()> {
-            let owner_balance = self.balance_of_impl(owner);
-            if owner_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
-            let prev_allowance = self.allowance_impl(owner, spender);
-            self.allowances
-                .insert((owner, spender), &(prev_allowance + value));
+            self.allowances.insert((owner, spender), &value);
             self.env().emit_event(Approval {
                 owner: *owner,
                 spender: *spender,
@@ -176,4 +305,186 @@ mod token {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::storage::traits::StorageKey;
+        use sp_core::{ecdsa, Pair};
+
+        token_traits::erc20_allowance_tests!(|supply| Token::new(
+            supply,
+            None,
+            None,
+            0,
+            Hash::default()
+        ));
+
+        #[ink::test]
+        fn metadata_works() {
+            let token = Token::new(
+                100,
+                Some(String::from("Token")),
+                Some(String::from("TKN")),
+                12,
+                Hash::default(),
+            );
+            assert_eq!(token.token_name(), Some(String::from("Token")));
+            assert_eq!(token.token_symbol(), Some(String::from("TKN")));
+            assert_eq!(token.token_decimals(), 12);
+        }
+
+        const GENESIS_HASH: [u8; 32] = [7; 32];
+        const DEADLINE: u64 = 1_000;
+
+        // A token with 50 tokens on the account of an ECDSA key, and that key.
+        fn permit_token() -> (Token, ecdsa::Pair, AccountId) {
+            let mut token = Token::new(100, None, None, 0, Hash::from(GENESIS_HASH));
+            let pair = ecdsa::Pair::from_seed(&[1; 32]);
+            let owner = AccountId::from(sp_core::blake2_256(pair.public().as_ref()));
+            assert_eq!(token.transfer(owner, 50), Ok(()));
+            (token, pair, owner)
+        }
+
+        // The signature of `pair` over a permit of `owner` for Bob on this token.
+        fn sign(
+            pair: &ecdsa::Pair,
+            genesis_hash: [u8; 32],
+            owner: AccountId,
+            value: Balance,
+            nonce: u64,
+        ) -> [u8; 65] {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let hash = token_traits::permit_hash(
+                Hash::from(genesis_hash),
+                ink::env::account_id::<ink::env::DefaultEnvironment>(),
+                owner,
+                accounts.bob,
+                value,
+                nonce,
+                DEADLINE,
+            );
+            pair.sign_prehashed(&hash).0
+        }
+
+        #[ink::test]
+        fn permit_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (mut token, pair, owner) = permit_token();
+            let signature = sign(&pair, GENESIS_HASH, owner, 30, 0);
+
+            // Bob submits the permit and spends the allowance.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Ok(())
+            );
+            assert_eq!(token.allowance(owner, accounts.bob), 30);
+            assert_eq!(token.nonces(owner), 1);
+            assert_eq!(token.transfer_from(owner, accounts.bob, 30), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 30);
+        }
+
+        #[ink::test]
+        fn expired_permit_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (mut token, pair, owner) = permit_token();
+            let signature = sign(&pair, GENESIS_HASH, owner, 30, 0);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(DEADLINE + 1);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Err(Error::PermitExpired)
+            );
+            assert_eq!(token.allowance(owner, accounts.bob), 0);
+            assert_eq!(token.nonces(owner), 0);
+        }
+
+        #[ink::test]
+        fn permit_of_wrong_signer_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (mut token, _, owner) = permit_token();
+            let other = ecdsa::Pair::from_seed(&[2; 32]);
+            let signature = sign(&other, GENESIS_HASH, owner, 30, 0);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(token.allowance(owner, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn permit_of_other_chain_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (mut token, pair, owner) = permit_token();
+            let signature = sign(&pair, [8; 32], owner, 30, 0);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn permit_replay_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (mut token, pair, owner) = permit_token();
+            let signature = sign(&pair, GENESIS_HASH, owner, 30, 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Ok(())
+            );
+            assert_eq!(token.transfer_from(owner, accounts.bob, 30), Ok(()));
+            // The nonce is used up, the same permit doesn't approve again.
+            assert_eq!(
+                token.permit(owner, accounts.bob, 30, DEADLINE, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(token.allowance(owner, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn migrate_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = Token::new(100, None, None, 0, Hash::default());
+            let genesis_hash = Hash::from([7; 32]);
+            assert_eq!(token.transfer(accounts.bob, 10), Ok(()));
+            // Only the admin can migrate or replace the code.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.migrate(genesis_hash), Err(MigrationError::NotAdmin));
+            assert_eq!(
+                token.set_code(Hash::from([1; 32])),
+                Err(MigrationError::NotAdmin)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                token.migrate(genesis_hash),
+                Err(MigrationError::AlreadyMigrated)
+            );
+
+            // The storage root from before versioning is only the total supply, there is
+            // no storage version, genesis hash or admin.
+            let root_key = <Token as StorageKey>::KEY;
+            ink::env::set_contract_storage(&root_key, &100u128);
+            ink::env::clear_contract_storage(&STORAGE_VERSION_KEY);
+            ink::env::clear_contract_storage(&token.genesis_hash.key());
+            ink::env::clear_contract_storage(&token.admin.key());
+            let mut token: Token = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(token.storage_version(), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.migrate(genesis_hash), Ok(1));
+            assert_eq!(
+                token.migrate(genesis_hash),
+                Err(MigrationError::AlreadyMigrated)
+            );
+
+            // Read it back.
+            ink::env::set_contract_storage(&root_key, &token);
+            let token: Token = ink::env::get_contract_storage(&root_key).unwrap().unwrap();
+            assert_eq!(token.storage_version(), 1);
+            assert_eq!(token.genesis_hash(), genesis_hash);
+            assert_eq!(token.total_supply(), 100);
+            assert_eq!(token.balance_of(accounts.alice), 90);
+            assert_eq!(token.balance_of(accounts.bob), 10);
+        }
+    }
 }
@@ -0,0 +1,384 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Token interfaces shared by the token contracts and the contracts calling them.
+//!
+//! The selectors of a trait message are namespaced by the trait, e.g. `Erc20::balance_of`
+//! or `PSP22::balance_of`, so a caller and a token only match when they use the same
+//! trait definition. The PSP22 traits are named as in the standard to match its
+//! selectors.
+
+use ink::env::hash::{Blake2x256, HashOutput};
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::{AccountId, Hash};
+
+pub type Balance = u128;
+
+/// The ERC-20 error types.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Error {
+    /// Returned if not enough balance to fulfill a request is available.
+    InsufficientBalance,
+    /// Returned if not enough allowance to fulfill a request is available.
+    InsufficientAllowance,
+    /// Returned if the caller does not have the role a request requires.
+    MissingRole,
+    /// Returned if the deadline of a permit has passed.
+    PermitExpired,
+    /// Returned if a permit is not signed by the owner.
+    InvalidSignature,
+    /// Returned if minting would overflow the total supply.
+    SupplyOverflow,
+    /// Returned if the permit nonce of the owner is used up.
+    NonceOverflow,
+}
+
+/// The ERC-20 result type.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The ERC-20 standard.
+#[ink::trait_definition]
+pub trait Erc20 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    /// Transfers `value` amount of tokens from the caller's account to account `to`.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()>;
+
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the `value` amount.
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()>;
+
+    /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+    #[ink(message)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()>;
+}
+
+/// Minting of ERC-20 tokens.
+#[ink::trait_definition]
+pub trait Erc20Mintable {
+    /// Creates `value` amount of tokens on the account `to`, increasing the total supply.
+    #[ink(message)]
+    fn mint(&mut self, to: AccountId, value: Balance) -> Result<()>;
+}
+
+/// Changing an ERC-20 allowance relative to its current value, which avoids the race
+/// of overwriting an allowance with `approve` while the spender uses it.
+#[ink::trait_definition]
+pub trait Erc20Allowance {
+    /// Increases the allowance of `spender` over the caller's account by `delta_value`.
+    #[ink(message)]
+    fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()>;
+
+    /// Decreases the allowance of `spender` over the caller's account by `delta_value`.
+    #[ink(message)]
+    fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<()>;
+}
+
+/// Signature based approvals of ERC-20 tokens (ERC-2612).
+#[ink::trait_definition]
+pub trait Erc20Permit {
+    /// Allows `spender` to withdraw up to the `value` amount from the account `owner`,
+    /// authorized by the ECDSA `signature` of `owner` over the `permit_hash`. The permit
+    /// is valid until the timestamp `deadline`.
+    #[ink(message)]
+    fn permit(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: Balance,
+        deadline: u64,
+        signature: [u8; 65],
+    ) -> Result<()>;
+
+    /// Returns the nonce the next permit of `owner` is signed with.
+    #[ink(message)]
+    fn nonces(&self, owner: AccountId) -> u64;
+}
+
+/// Returns the hash a permit of `owner` for the token contract `token` on the chain with
+/// `genesis_hash` is signed over.
+///
+/// The hash is domain separated by the chain, the token contract and the nonce of
+/// `owner`, so a signature is valid for a single permit on a single token. A token
+/// deployed at the same address on another chain doesn't accept it.
+pub fn permit_hash(
+    genesis_hash: Hash,
+    token: AccountId,
+    owner: AccountId,
+    spender: AccountId,
+    value: Balance,
+    nonce: u64,
+    deadline: u64,
+) -> [u8; 32] {
+    let mut hash = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_encoded::<Blake2x256, _>(
+        &(
+            b"Erc20Permit",
+            genesis_hash,
+            token,
+            owner,
+            spender,
+            value,
+            nonce,
+            deadline,
+        ),
+        &mut hash,
+    );
+    hash
+}
+
+/// Returns the account that signed `message_hash` with the ECDSA `signature`, i.e. the
+/// BLAKE2 hash of its compressed public key.
+pub fn recover_signer(signature: &[u8; 65], message_hash: &[u8; 32]) -> Option<AccountId> {
+    let mut public_key = [0; 33];
+    ink::env::ecdsa_recover(signature, message_hash, &mut public_key).ok()?;
+    let mut account = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+    Some(AccountId::from(account))
+}
+
+/// Burning of ERC-20 tokens.
+#[ink::trait_definition]
+pub trait Erc20Burnable {
+    /// Destroys `value` amount of tokens from the account `from`, reducing the total
+    /// supply.
+    #[ink(message)]
+    fn burn(&mut self, from: AccountId, value: Balance) -> Result<()>;
+}
+
+/// The PSP22 error types.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Psp22Error {
+    /// Custom error type for cases in which an implementation adds its own restrictions.
+    Custom(String),
+    /// Returned if not enough balance to fulfill a request is available.
+    InsufficientBalance,
+    /// Returned if not enough allowance to fulfill a request is available.
+    InsufficientAllowance,
+    /// Returned if recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check fails.
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 standard, with `PSP22::` namespaced selectors.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    /// Transfers `value` amount of tokens from the caller's account to account `to`.
+    #[ink(message)]
+    fn transfer(
+        &mut self,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> core::result::Result<(), Psp22Error>;
+
+    /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> core::result::Result<(), Psp22Error>;
+
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the `value` amount.
+    #[ink(message)]
+    fn approve(
+        &mut self,
+        spender: AccountId,
+        value: Balance,
+    ) -> core::result::Result<(), Psp22Error>;
+}
+
+/// The metadata of a PSP22 token, with `PSP22Metadata::` namespaced selectors.
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    /// Returns the name of the token, if set.
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    /// Returns the symbol of the token, if set.
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    /// Returns the number of decimals a balance is shown with.
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
+/// Burning of PSP22 tokens, with `PSP22Burnable::` namespaced selectors.
+#[ink::trait_definition]
+pub trait PSP22Burnable {
+    /// Destroys `value` amount of tokens from `account`, reducing the total supply.
+    #[ink(message)]
+    fn burn(&mut self, account: AccountId, value: Balance) -> core::result::Result<(), Psp22Error>;
+}
+
+/// The ERC-1155 token id type.
+pub type TokenId = u128;
+
+/// The ERC-1155 error types.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Erc1155Error {
+    /// Returned if the token id does not exist.
+    TokenNotFound,
+    /// Returned if not enough balance to fulfill a request is available.
+    InsufficientBalance,
+    /// Returned if the caller is neither the owner nor an approved operator.
+    NotApproved,
+    /// Returned if minting would overflow the supply of the token id.
+    SupplyOverflow,
+}
+
+/// The ERC-1155 multi token standard, with the supply of a token id (`ERC1155Supply`).
+#[ink::trait_definition]
+pub trait Erc1155 {
+    /// Returns the balance of `owner` for the token `token_id`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId, token_id: TokenId) -> Balance;
+
+    /// Returns the balances of the (`owner`, `token_id`) pairs, up to the shortest of
+    /// both lists.
+    #[ink(message)]
+    fn balance_of_batch(&self, owners: Vec<AccountId>, token_ids: Vec<TokenId>) -> Vec<Balance>;
+
+    /// Returns the total supply of the token `token_id`.
+    #[ink(message)]
+    fn total_supply(&self, token_id: TokenId) -> Balance;
+
+    /// Transfers `value` of the token `token_id` from `from` to `to`.
+    #[ink(message)]
+    fn safe_transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        token_id: TokenId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> core::result::Result<(), Erc1155Error>;
+
+    /// Allows or disallows `operator` to transfer all tokens of the caller.
+    #[ink(message)]
+    fn set_approval_for_all(
+        &mut self,
+        operator: AccountId,
+        approved: bool,
+    ) -> core::result::Result<(), Erc1155Error>;
+
+    /// Returns whether `operator` may transfer all tokens of `owner`.
+    #[ink(message)]
+    fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool;
+}
+
+/// The tests of the ERC-20 allowance semantics that every token contract runs against
+/// itself, so the tokens behave the same for the same calls.
+///
+/// Expands to `#[ink::test]`s in the test module of a token, with `$new` creating the
+/// token with the given supply for the caller (Alice), e.g.
+///
+/// ```ignore
+/// token_traits::erc20_allowance_tests!(|supply| {
+///     Erc20::new(supply, None, None, 0, Hash::default())
+/// });
+/// ```
+#[cfg(feature = "test-suite")]
+#[macro_export]
+macro_rules! erc20_allowance_tests {
+    ($new:expr) => {
+        /// `approve` overwrites the allowance, also with more than the balance.
+        #[ink::test]
+        fn approve_overwrites_allowance() {
+            use $crate::Erc20 as _;
+            let mut token = ($new)(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(token.approve(accounts.bob, 5), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 5);
+            assert_eq!(token.approve(accounts.bob, 200), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 200);
+            assert_eq!(token.approve(accounts.bob, 0), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+        }
+
+        /// `increase_allowance` adds to the allowance.
+        #[ink::test]
+        fn increase_allowance_works() {
+            use $crate::{Erc20 as _, Erc20Allowance as _};
+            let mut token = ($new)(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.increase_allowance(accounts.bob, 10), Ok(()));
+            assert_eq!(token.increase_allowance(accounts.bob, 5), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 15);
+        }
+
+        /// `decrease_allowance` subtracts from the allowance and can't go below zero.
+        #[ink::test]
+        fn decrease_allowance_works() {
+            use $crate::{Erc20 as _, Erc20Allowance as _};
+            let mut token = ($new)(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(token.decrease_allowance(accounts.bob, 4), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 6);
+            assert_eq!(
+                token.decrease_allowance(accounts.bob, 7),
+                Err($crate::Error::InsufficientAllowance)
+            );
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 6);
+            assert_eq!(token.decrease_allowance(accounts.bob, 6), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+        }
+
+        /// `transfer_from` spends the allowance and fails without enough of it.
+        #[ink::test]
+        fn transfer_from_spends_allowance() {
+            use $crate::Erc20 as _;
+            let mut token = ($new)(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.eve, 11),
+                Err($crate::Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.eve, 10),
+                Ok(())
+            );
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(token.balance_of(accounts.alice), 90);
+            assert_eq!(token.balance_of(accounts.eve), 10);
+        }
+    };
+}